@@ -1,31 +1,277 @@
+use std::collections::HashSet;
+
 use aoc_runner_derive::{aoc, aoc_generator};
+
 #[aoc_generator(day10)]
-fn parse(_input: &str) -> String {
-    todo!()
+fn parse(input: &str) -> HeightMap {
+    let heights = input
+        .lines()
+        .map(|line| {
+            line.chars()
+                .map(|c| match c {
+                    // Impassable tiles, which show up in some of the puzzle's examples.
+                    '.' => None,
+                    _ => Some(c.to_digit(10).expect("That height wasn't a digit or a `.`.") as u8),
+                })
+                .collect()
+        })
+        .collect();
+
+    HeightMap { heights }
 }
 
 #[aoc(day10, part1)]
-fn part1(_input: &str) -> String {
-    todo!()
+fn part1(map: &HeightMap) -> usize {
+    map.trailheads().iter().map(|trailhead| trailhead.score).sum()
 }
 
 #[aoc(day10, part2)]
-fn part2(_input: &str) -> String {
-    todo!()
+fn part2(map: &HeightMap) -> usize {
+    map.trailheads().iter().map(|trailhead| trailhead.rating).sum()
+}
+
+/// The lowest height, where every hiking trail starts.
+const TRAILHEAD: u8 = 0;
+
+/// The highest height, where every hiking trail ends.
+const SUMMIT: u8 = 9;
+
+/// Represents a `(x, y)` coordinate pair.
+type Coord = (usize, usize);
+
+/// A spot where hiking trails start, and how good of a start it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Trailhead {
+    coord: Coord,
+    /// The number of distinct summits reachable from this trailhead.
+    score: usize,
+    /// The number of distinct hiking trails starting at this trailhead.
+    rating: usize,
+}
+
+/// The topographic map of the lava production facility's surroundings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HeightMap {
+    /// The height of each spot, or `None` if the spot is impassable.
+    heights: Vec<Vec<Option<u8>>>,
 }
 
+impl HeightMap {
+    /// Returns the height at `(x, y)`.
+    ///
+    /// Returns `None` if `(x, y)` is impassable or outside the map.
+    fn height_at(&self, (x, y): Coord) -> Option<u8> {
+        self.heights.get(y)?.get(x).copied().flatten()
+    }
+
+    /// Returns the passable spots next to `(x, y)` (no diagonals).
+    fn neighbours(&self, (x, y): Coord) -> impl Iterator<Item = Coord> + '_ {
+        [
+            y.checked_sub(1).map(|y| (x, y)),
+            Some((x + 1, y)),
+            Some((x, y + 1)),
+            x.checked_sub(1).map(|x| (x, y)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|coord| self.height_at(*coord).is_some())
+    }
+
+    /// Returns every trailhead on the map in reading order, along with its score and rating.
+    ///
+    /// Every hiking trail climbs exactly one height per step, so the summits (and trails) that
+    /// can be reached from a spot only depend on the spots one height above it. Working down
+    /// from the summits, each spot is visited once instead of once per trail that crosses it.
+    fn trailheads(&self) -> Vec<Trailhead> {
+        let mut by_height: Vec<Vec<Coord>> = vec![Vec::new(); SUMMIT as usize + 1];
+        for (y, row) in self.heights.iter().enumerate() {
+            for (x, height) in row.iter().enumerate() {
+                if let Some(height) = height.filter(|height| *height <= SUMMIT) {
+                    by_height[height as usize].push((x, y));
+                }
+            }
+        }
+
+        let width = self.heights.iter().map(Vec::len).max().unwrap_or(0);
+        let index = |(x, y): Coord| y * width + x;
+
+        // The summits reachable from each spot, and the number of trails from it to any summit.
+        let mut summits: Vec<HashSet<Coord>> = vec![HashSet::new(); width * self.heights.len()];
+        let mut trails: Vec<usize> = vec![0; width * self.heights.len()];
+
+        for coord in &by_height[SUMMIT as usize] {
+            summits[index(*coord)].insert(*coord);
+            trails[index(*coord)] = 1;
+        }
+
+        for height in (TRAILHEAD..SUMMIT).rev() {
+            for coord in &by_height[height as usize] {
+                let above: Vec<Coord> = self
+                    .neighbours(*coord)
+                    .filter(|next| self.height_at(*next) == Some(height + 1))
+                    .collect();
+
+                for next in above {
+                    let reachable = summits[index(next)].clone();
+                    summits[index(*coord)].extend(reachable);
+                    trails[index(*coord)] += trails[index(next)];
+                }
+            }
+        }
+
+        by_height[TRAILHEAD as usize]
+            .iter()
+            .map(|coord| Trailhead {
+                coord: *coord,
+                score: summits[index(*coord)].len(),
+                rating: trails[index(*coord)],
+            })
+            .collect()
+    }
+
+    /// Returns every distinct hiking trail starting at `trailhead`, as the spots along the trail
+    /// from the trailhead to a summit. Stops looking once `limit` trails were found, if given.
+    ///
+    /// Returns nothing if `trailhead` isn't a trailhead.
+    #[allow(dead_code)]
+    fn trails(&self, trailhead: Coord, limit: Option<usize>) -> Vec<Vec<Coord>> {
+        let mut trails = Vec::new();
+        if self.height_at(trailhead) == Some(TRAILHEAD) {
+            let mut trail = vec![trailhead];
+            self.climb(&mut trail, limit.unwrap_or(usize::MAX), &mut trails);
+        }
+        trails
+    }
+
+    fn climb(&self, trail: &mut Vec<Coord>, limit: usize, trails: &mut Vec<Vec<Coord>>) {
+        // SAFETY: `trail` always starts with the trailhead.
+        let coord = *trail.last().unwrap();
+        // SAFETY: Every spot on `trail` has a height.
+        let height = self.height_at(coord).unwrap();
+
+        if height == SUMMIT {
+            trails.push(trail.clone());
+            return;
+        }
+
+        for next in self.neighbours(coord) {
+            if trails.len() >= limit {
+                return;
+            }
+            if self.height_at(next) != Some(height + 1) {
+                continue;
+            }
+
+            trail.push(next);
+            self.climb(trail, limit, trails);
+            trail.pop();
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "\
+89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732";
+
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse("<EXAMPLE>")), "<RESULT>");
+        assert_eq!(part1(&parse(EXAMPLE)), 36);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse("<EXAMPLE>")), "<RESULT>");
+        assert_eq!(part2(&parse(EXAMPLE)), 81);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn trailheads_example() {
+        let trailheads = parse(EXAMPLE).trailheads();
+        let scores: Vec<_> = trailheads.iter().map(|trailhead| trailhead.score).collect();
+        let ratings: Vec<_> = trailheads.iter().map(|trailhead| trailhead.rating).collect();
+
+        // The puzzle lists both in reading order.
+        assert_eq!(scores, [5, 6, 5, 3, 1, 3, 5, 3, 5]);
+        assert_eq!(ratings, [20, 24, 10, 4, 1, 4, 5, 8, 5]);
+        assert_eq!(trailheads[0].coord, (2, 0));
+    }
+
+    #[test]
+    fn single_trail() {
+        let map = parse("0123456789");
+        assert_eq!(
+            map.trailheads(),
+            [Trailhead {
+                coord: (0, 0),
+                score: 1,
+                rating: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn impassable_tiles() {
+        let map = parse(
+            "\
+...0...
+...1...
+...2...
+6543456
+7.....7
+8.....8
+9.....9",
+        );
+        assert_eq!(part1(&map), 2);
+
+        let map = parse(
+            "\
+10..9..
+2...8..
+3...7..
+4567654
+...8..3
+...9..2
+.....01",
+        );
+        assert_eq!(part1(&map), 3);
+    }
+
+    #[test]
+    fn trails_example() {
+        let map = parse(
+            "\
+.....0.
+..4321.
+..5..2.
+..6543.
+..7..4.
+..8765.
+..9....",
+        );
+        assert_eq!(part2(&map), 3);
+
+        let trails = map.trails((5, 0), None);
+        assert_eq!(trails.len(), 3);
+        assert_eq!(
+            trails[0],
+            [(5, 0), (5, 1), (5, 2), (5, 3), (5, 4), (5, 5), (4, 5), (3, 5), (2, 5), (2, 6)]
+        );
+        for trail in &trails {
+            assert_eq!(trail.len(), 10);
+            assert_eq!(trail.last(), Some(&(2, 6)));
+        }
+
+        assert_eq!(map.trails((5, 0), Some(2)), trails[..2]);
+        // Not a trailhead.
+        assert!(map.trails((5, 1), None).is_empty());
+    }
+}
@@ -1,31 +1,434 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    ops::AddAssign,
+};
+
 use aoc_runner_derive::{aoc, aoc_generator};
+
 #[aoc_generator(day11)]
-fn parse(_input: &str) -> String {
-    todo!()
+fn parse(input: &str) -> Vec<u64> {
+    input
+        .split_whitespace()
+        .map(|n| n.parse().expect("That stone wasn't engraved with a number."))
+        .collect()
 }
 
 #[aoc(day11, part1)]
-fn part1(_input: &str) -> String {
-    todo!()
+fn part1(stones: &[u64]) -> StoneCount {
+    let rules = RuleSet::puzzle();
+    Stones::new(stones, &rules).blink(25).total()
 }
 
 #[aoc(day11, part2)]
-fn part2(_input: &str) -> String {
-    todo!()
+fn part2(stones: &[u64]) -> StoneCount {
+    let rules = RuleSet::puzzle();
+    Stones::new(stones, &rules).blink(75).total()
+}
+
+/// Decides whether a [Rule] applies to a stone engraved with the given number.
+type RuleFilter = dyn Fn(u64) -> bool;
+
+/// Returns the numbers on the stones that a stone engraved with the given number turns into.
+type RuleTransform = dyn Fn(u64) -> Vec<u64>;
+
+/// One of the rules that decide how a stone changes when the historians blink.
+struct Rule {
+    applies: Box<RuleFilter>,
+    transform: Box<RuleTransform>,
+}
+
+impl Rule {
+    fn new(
+        applies: impl Fn(u64) -> bool + 'static,
+        transform: impl Fn(u64) -> Vec<u64> + 'static,
+    ) -> Self {
+        Self {
+            applies: Box::new(applies),
+            transform: Box::new(transform),
+        }
+    }
+}
+
+/// The rules a stone follows when the historians blink, in order of priority: a stone changes
+/// according to the first rule that applies to it, and stays the same if none of them do.
+struct RuleSet(Vec<Rule>);
+
+impl RuleSet {
+    /// The rules from the puzzle.
+    fn puzzle() -> Self {
+        Self(vec![
+            Rule::new(|stone| stone == 0, |_| vec![1]),
+            Rule::new(
+                |stone| (stone.ilog10() + 1).is_multiple_of(2),
+                |stone| {
+                    let half = 10u64.pow(stone.ilog10().div_ceil(2));
+                    vec![stone / half, stone % half]
+                },
+            ),
+            Rule::new(
+                |_| true,
+                |stone| vec![stone.checked_mul(2024).expect("That stone's number got too big for a `u64`.")],
+            ),
+        ])
+    }
+
+    /// Returns the numbers on the stones that a stone engraved with `stone` turns into.
+    fn apply(&self, stone: u64) -> Vec<u64> {
+        match self.0.iter().find(|rule| (rule.applies)(stone)) {
+            Some(rule) => (rule.transform)(stone),
+            None => vec![stone],
+        }
+    }
+}
+
+/// The stones in a line, counted by the number engraved on them.
+///
+/// Every stone changes on its own, without caring about its neighbours, so the order of the
+/// stones doesn't matter. Stones with the same number always change the same way, so they
+/// only need to be changed once per blink no matter how many of them there are.
+///
+/// Only a few thousand different numbers ever show up, so each one gets an index, and what
+/// it turns into is remembered the first time it's worked out.
+#[derive(Clone)]
+struct Stones<'r> {
+    rules: &'r RuleSet,
+    /// The number engraved on the stones at each index.
+    numbers: Vec<u64>,
+    /// The index of each number in `numbers`.
+    index: HashMap<u64, usize>,
+    /// What the stones at each index turn into after a blink, as indices.
+    /// `None` until it's needed for the first time.
+    changes: Vec<Option<Vec<usize>>>,
+    /// How many stones there are at each index.
+    counts: Vec<StoneCount>,
+    /// The counts from the previous blink, kept around to avoid allocating new ones.
+    spare: Vec<StoneCount>,
+}
+
+impl<'r> Stones<'r> {
+    fn new(stones: &[u64], rules: &'r RuleSet) -> Self {
+        let mut counts = Self {
+            rules,
+            numbers: Vec::new(),
+            index: HashMap::new(),
+            changes: Vec::new(),
+            counts: Vec::new(),
+            spare: Vec::new(),
+        };
+
+        for stone in stones {
+            let idx = counts.index_of(*stone);
+            counts.counts[idx] += &StoneCount::from(1);
+        }
+        counts
+    }
+
+    /// Returns the index of `stone`, giving it one if it doesn't have one yet.
+    fn index_of(&mut self, stone: u64) -> usize {
+        *self.index.entry(stone).or_insert_with(|| {
+            self.numbers.push(stone);
+            self.changes.push(None);
+            self.counts.push(StoneCount::default());
+            self.numbers.len() - 1
+        })
+    }
+
+    /// Works out what the stones at `idx` turn into after a blink, if it wasn't already.
+    fn work_out_change(&mut self, idx: usize) {
+        if self.changes[idx].is_some() {
+            return;
+        }
+
+        let change = self
+            .rules
+            .apply(self.numbers[idx])
+            .into_iter()
+            .map(|stone| self.index_of(stone))
+            .collect();
+        self.changes[idx] = Some(change);
+    }
+
+    /// Returns the stones after blinking `blinks` times.
+    fn blink(mut self, blinks: usize) -> Self {
+        for _ in 0..blinks {
+            self.blink_once();
+        }
+        self
+    }
+
+    fn blink_once(&mut self) {
+        // Work out what every number turns into first, since that can add new numbers.
+        for idx in 0..self.counts.len() {
+            if !self.counts[idx].is_zero() {
+                self.work_out_change(idx);
+            }
+        }
+
+        let mut next = std::mem::take(&mut self.spare);
+        next.resize_with(self.counts.len(), StoneCount::default);
+        next.iter_mut().for_each(StoneCount::clear);
+
+        for (count, change) in self.counts.iter().zip(&self.changes) {
+            if count.is_zero() {
+                continue;
+            }
+
+            // SAFETY: Every number with stones had its change worked out above.
+            for idx in change.as_ref().unwrap() {
+                next[*idx] += count;
+            }
+        }
+
+        self.spare = std::mem::replace(&mut self.counts, next);
+    }
+
+    /// Blinks `blinks` times, keeping track of how many distinct numbers there are after
+    /// each blink.
+    #[allow(dead_code)]
+    fn analyze(mut self, blinks: usize) -> Analysis {
+        let mut distinct = Vec::with_capacity(blinks + 1);
+        let mut stable_after = None;
+        let mut prev = self.distinct_numbers();
+        distinct.push(prev.len());
+
+        for blink in 1..=blinks {
+            self.blink_once();
+
+            let numbers = self.distinct_numbers();
+            distinct.push(numbers.len());
+            // Which numbers show up after a blink only depends on which numbers were there
+            // before it, so once they stop changing they never change again.
+            if stable_after.is_none() && numbers == prev {
+                stable_after = Some(blink - 1);
+            }
+            prev = numbers;
+        }
+
+        Analysis { distinct, stable_after }
+    }
+
+    /// Returns the indices of the numbers on at least one stone, in ascending order.
+    fn distinct_numbers(&self) -> Vec<usize> {
+        (0..self.counts.len()).filter(|idx| !self.counts[*idx].is_zero()).collect()
+    }
+
+    /// Returns how many stones there are with each number.
+    #[allow(dead_code)]
+    fn counts(&self) -> HashMap<u64, StoneCount> {
+        self.distinct_numbers()
+            .into_iter()
+            .map(|idx| (self.numbers[idx], self.counts[idx].clone()))
+            .collect()
+    }
+
+    /// Returns the total number of stones.
+    fn total(&self) -> StoneCount {
+        let mut total = StoneCount::default();
+        for count in &self.counts {
+            total += count;
+        }
+        total
+    }
+}
+
+/// How the distinct numbers on the stones change from blink to blink (see [Stones::analyze]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Analysis {
+    /// The number of distinct numbers after each blink, starting with the stones before blinking.
+    distinct: Vec<usize>,
+    /// The first blink after which the set of distinct numbers stopped changing, if it did.
+    stable_after: Option<usize>,
 }
 
+/// A number of stones. Stones multiply quickly enough to overflow any primitive integer after a
+/// few hundred blinks, so this grows as big as it needs to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct StoneCount {
+    /// The digits of the count in base 2^64, least significant first. Never ends with a zero.
+    limbs: Vec<u64>,
+}
+
+impl StoneCount {
+    fn is_zero(&self) -> bool { self.limbs.is_empty() }
+
+    /// Sets the count back to zero, without giving up its memory.
+    fn clear(&mut self) { self.limbs.clear() }
+}
+
+impl From<u64> for StoneCount {
+    fn from(value: u64) -> Self {
+        let limbs = if value == 0 { Vec::new() } else { vec![value] };
+        Self { limbs }
+    }
+}
+
+impl AddAssign<&StoneCount> for StoneCount {
+    fn add_assign(&mut self, rhs: &StoneCount) {
+        if self.limbs.len() < rhs.limbs.len() {
+            self.limbs.resize(rhs.limbs.len(), 0);
+        }
+
+        let mut carry = false;
+        for (idx, limb) in self.limbs.iter_mut().enumerate() {
+            let other = rhs.limbs.get(idx).copied().unwrap_or(0);
+            if other == 0 && !carry {
+                // Nothing left to add, since the rest of `rhs` is shorter than `self`.
+                if idx >= rhs.limbs.len() {
+                    break;
+                }
+                continue;
+            }
+
+            let (sum, overflowed) = limb.overflowing_add(other);
+            let (sum, carried) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = overflowed || carried;
+        }
+
+        if carry {
+            self.limbs.push(1);
+        }
+    }
+}
+
+impl Display for StoneCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        /// The largest power of ten that fits in a `u64`.
+        const CHUNK: u128 = 10u128.pow(19);
+
+        if self.limbs.is_empty() {
+            return write!(f, "0");
+        }
+
+        // Peel off 19 decimal digits at a time by dividing by 10^19, least significant first.
+        let mut limbs = self.limbs.clone();
+        let mut chunks = Vec::new();
+        while !limbs.is_empty() {
+            let mut remainder = 0u128;
+            for limb in limbs.iter_mut().rev() {
+                let value = (remainder << 64) | *limb as u128;
+                *limb = (value / CHUNK) as u64;
+                remainder = value % CHUNK;
+            }
+
+            chunks.push(remainder as u64);
+            while limbs.last() == Some(&0) {
+                limbs.pop();
+            }
+        }
+
+        // SAFETY: The count isn't zero, so there's at least one chunk.
+        let (last, rest) = chunks.split_last().unwrap();
+        write!(f, "{last}")?;
+        for chunk in rest.iter().rev() {
+            write!(f, "{chunk:019}")?;
+        }
+
+        Ok(())
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "125 17";
+
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse("<EXAMPLE>")), "<RESULT>");
+        assert_eq!(part1(&parse(EXAMPLE)), StoneCount::from(55312));
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse("<EXAMPLE>")), "<RESULT>");
+        assert_eq!(part2(&parse(EXAMPLE)).to_string(), "65601038650482");
+    }
+
+    #[test]
+    fn blink_example() {
+        let rules = RuleSet::puzzle();
+        let stones = Stones::new(&parse(EXAMPLE), &rules);
+        assert_eq!(stones.clone().blink(1).counts(), Stones::new(&[253000, 1, 7], &rules).counts());
+
+        let stones = stones.blink(6);
+        let expected = Stones::new(
+            &parse("2097446912 14168 4048 2 0 2 4 40 48 2024 40 48 80 96 2 8 6 7 6 0 3 2"),
+            &rules,
+        );
+        assert_eq!(stones.counts(), expected.counts());
+        assert_eq!(stones.total(), StoneCount::from(22));
+    }
+
+    #[test]
+    fn many_blinks() {
+        let rules = RuleSet::puzzle();
+        let total = Stones::new(&parse(EXAMPLE), &rules).blink(500).total();
+        // Far past what a `u128` can hold.
+        assert!(total.limbs.len() > 2);
+        assert!(total.to_string().len() > 39);
+    }
+
+    #[test]
+    fn custom_rules() {
+        // Halve every stone until it's a 1, which never changes.
+        let rules = RuleSet(vec![
+            Rule::new(|stone| stone == 1, |stone| vec![stone]),
+            Rule::new(|_| true, |stone| vec![stone / 2, stone - stone / 2]),
+        ]);
+        let stones = Stones::new(&[8], &rules);
+        assert_eq!(stones.clone().blink(2).counts(), HashMap::from([(2, StoneCount::from(4))]));
+        assert_eq!(stones.clone().blink(10).total(), StoneCount::from(8));
+
+        let analysis = stones.analyze(5);
+        assert_eq!(analysis.distinct, [1, 1, 1, 1, 1, 1]);
+        assert_eq!(analysis.stable_after, Some(3));
+
+        // Stones that no rule applies to stay the same.
+        let rules = RuleSet(vec![Rule::new(|stone| stone > 100, |stone| vec![stone - 100])]);
+        let stones = Stones::new(&[250, 7], &rules).blink(3);
+        assert_eq!(stones.counts(), HashMap::from([(50, StoneCount::from(1)), (7, StoneCount::from(1))]));
+    }
+
+    #[test]
+    fn analyze_cycles() {
+        // The Collatz rules keep a single stone cycling through 4, 2, 1 forever...
+        let rules = RuleSet(vec![
+            Rule::new(|stone| stone % 2 == 0, |stone| vec![stone / 2]),
+            Rule::new(|_| true, |stone| vec![stone * 3 + 1]),
+        ]);
+        let analysis = Stones::new(&[4], &rules).analyze(6);
+        assert_eq!(analysis.distinct, [1; 7]);
+        assert_eq!(analysis.stable_after, None);
+
+        // ...but with one stone of each, the set of numbers never changes.
+        let analysis = Stones::new(&[1, 2, 4], &rules).analyze(3);
+        assert_eq!(analysis.distinct, [3; 4]);
+        assert_eq!(analysis.stable_after, Some(0));
+    }
+
+    #[test]
+    fn analyze_puzzle_rules() {
+        let rules = RuleSet::puzzle();
+        let analysis = Stones::new(&parse(EXAMPLE), &rules).analyze(100);
+        assert_eq!(analysis.distinct[..4], [2, 3, 4, 5]);
+
+        // The numbers settle down long before 100 blinks, and stay settled.
+        let stable_after = analysis.stable_after.unwrap();
+        assert!(analysis.distinct[stable_after..].iter().all(|distinct| *distinct == analysis.distinct[100]));
+    }
+
+    #[test]
+    fn stone_count_carries() {
+        let mut count = StoneCount::from(u64::MAX);
+        count += &StoneCount::from(u64::MAX);
+        assert_eq!(count.to_string(), (u64::MAX as u128 * 2).to_string());
+
+        count += &StoneCount::from(2);
+        assert_eq!(count.limbs, [0, 2]);
+        assert_eq!(count.to_string(), "36893488147419103232");
+
+        assert_eq!(StoneCount::default().to_string(), "0");
+        assert_eq!(StoneCount::from(10u64.pow(19)).to_string(), "10000000000000000000");
     }
 }
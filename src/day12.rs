@@ -1,31 +1,774 @@
 use aoc_runner_derive::{aoc, aoc_generator};
+
 #[aoc_generator(day12)]
-fn parse(_input: &str) -> String {
-    todo!()
+fn parse(input: &str) -> Garden {
+    Garden {
+        plants: input.lines().map(|line| line.chars().collect()).collect(),
+    }
 }
 
 #[aoc(day12, part1)]
-fn part1(_input: &str) -> String {
-    todo!()
+fn part1(garden: &Garden) -> usize {
+    garden
+        .regions()
+        .iter()
+        .map(|region| region.area * region.perimeter)
+        .sum()
 }
 
 #[aoc(day12, part2)]
-fn part2(_input: &str) -> String {
-    todo!()
+fn part2(garden: &Garden) -> usize {
+    garden
+        .regions()
+        .iter()
+        .map(|region| region.area * region.sides)
+        .sum()
+}
+
+/// Represents a `(x, y)` coordinate pair.
+type Coord = (usize, usize);
+
+/// The garden plots, each labeled with the type of plant growing there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Garden {
+    plants: Vec<Vec<char>>,
+}
+
+/// A group of touching garden plots that all grow the same type of plant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Region {
+    plant: char,
+    /// The number of plots in the region.
+    area: usize,
+    /// The number of plot edges that touch a different region, or the edge of the map.
+    perimeter: usize,
+    /// The number of straight sections of fence around the region.
+    sides: usize,
 }
 
+impl Garden {
+    /// Returns the plant growing at `(x, y)`, or `None` if `(x, y)` is outside the garden.
+    fn plant_at(&self, (x, y): Coord) -> Option<char> {
+        self.plants.get(y)?.get(x).copied()
+    }
+
+    /// Returns the plots next to `(x, y)` (no diagonals), including the ones outside the garden.
+    fn neighbours((x, y): Coord) -> impl Iterator<Item = Coord> {
+        [
+            y.checked_sub(1).map(|y| (x, y)),
+            Some((x + 1, y)),
+            Some((x, y + 1)),
+            x.checked_sub(1).map(|x| (x, y)),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Returns which region each plot belongs to, and the number of regions.
+    ///
+    /// This needs the whole garden in memory at once, so it's only used where every plot's
+    /// region is needed. [Garden::regions] gets by with a couple of rows at a time.
+    ///
+    /// Regions are numbered in the order their first plot shows up, in reading order.
+    /// The same type of plant can grow in several regions, as long as they don't touch.
+    fn label(&self) -> (Vec<Vec<usize>>, usize) {
+        let mut labels: Vec<Vec<usize>> = self
+            .plants
+            .iter()
+            .map(|row| vec![usize::MAX; row.len()])
+            .collect();
+        let mut count = 0;
+        // Flood fill with a stack, so big regions can't overflow the real one.
+        let mut stack = Vec::new();
+
+        for (y, row) in self.plants.iter().enumerate() {
+            for (x, plant) in row.iter().enumerate() {
+                if labels[y][x] != usize::MAX {
+                    continue;
+                }
+
+                labels[y][x] = count;
+                stack.push((x, y));
+                while let Some(coord) = stack.pop() {
+                    for (nx, ny) in Self::neighbours(coord) {
+                        if self.plant_at((nx, ny)) == Some(*plant) && labels[ny][nx] == usize::MAX {
+                            labels[ny][nx] = count;
+                            stack.push((nx, ny));
+                        }
+                    }
+                }
+                count += 1;
+            }
+        }
+
+        (labels, count)
+    }
+
+    /// Returns every region in the garden, in the order their first plot shows up.
+    fn regions(&self) -> Vec<Region> {
+        let mut stream = RegionStream::default();
+        for row in &self.plants {
+            stream.push_row(row);
+        }
+        stream.finish()
+    }
+
+    /// Measures the regions given by `labels` (see [Garden::label]).
+    fn measure(&self, labels: &[Vec<usize>], count: usize) -> Vec<Region> {
+        let label_at = |x: isize, y: isize| {
+            let (x, y) = (usize::try_from(x).ok()?, usize::try_from(y).ok()?);
+            labels.get(y)?.get(x).copied()
+        };
+
+        let mut regions: Vec<Option<Region>> = vec![None; count];
+        for (y, row) in labels.iter().enumerate() {
+            for (x, label) in row.iter().enumerate() {
+                let region = regions[*label].get_or_insert_with(|| Region {
+                    plant: self.plants[y][x],
+                    area: 0,
+                    perimeter: 0,
+                    sides: 0,
+                });
+                let (x, y) = (x as isize, y as isize);
+                let same = |dx: isize, dy: isize| label_at(x + dx, y + dy) == Some(*label);
+
+                region.area += 1;
+                region.perimeter += [(0, -1), (1, 0), (0, 1), (-1, 0)]
+                    .into_iter()
+                    .filter(|(dx, dy)| !same(*dx, *dy))
+                    .count();
+
+                // A region has as many sides as it has corners. Each corner of a plot is an
+                // outside corner if neither plot beside it is in the region, and an inside
+                // corner if both are but the plot diagonally across isn't.
+                region.sides += [(-1, -1), (1, -1), (1, 1), (-1, 1)]
+                    .into_iter()
+                    .filter(|(dx, dy)| {
+                        let (across, down) = (same(*dx, 0), same(0, *dy));
+                        (!across && !down) || (across && down && !same(*dx, *dy))
+                    })
+                    .count();
+            }
+        }
+
+        // SAFETY: Every label up to `count` was given to at least one plot.
+        regions.into_iter().map(Option::unwrap).collect()
+    }
+
+    /// Returns a report on every region in the garden, in the same order as [Garden::regions].
+    #[allow(dead_code)]
+    fn analyze(&self) -> Vec<RegionReport> {
+        let (labels, count) = self.label();
+        let regions = self.measure(&labels, count);
+
+        let mut bounds: Vec<Option<Bounds>> = vec![None; count];
+        for (y, row) in labels.iter().enumerate() {
+            for (x, label) in row.iter().enumerate() {
+                bounds[*label]
+                    .get_or_insert(Bounds { min: (x, y), max: (x, y) })
+                    .extend((x, y));
+            }
+        }
+        // SAFETY: Every label up to `count` was given to at least one plot.
+        let bounds: Vec<Bounds> = bounds.into_iter().map(Option::unwrap).collect();
+
+        let mut holes = vec![0; count];
+        let mut enclosed_by: Vec<Vec<usize>> = vec![Vec::new(); count];
+        for (label, bounds) in bounds.iter().enumerate() {
+            for hole in Self::holes(&labels, label, bounds) {
+                holes[label] += 1;
+                for inner in hole {
+                    if !enclosed_by[inner].contains(&label) {
+                        enclosed_by[inner].push(label);
+                    }
+                }
+            }
+        }
+
+        regions
+            .into_iter()
+            .zip(bounds)
+            .enumerate()
+            .map(|(label, (region, bounds))| RegionReport {
+                region,
+                bounds,
+                holes: holes[label],
+                // Every region around this one also surrounds the innermost one, so the
+                // innermost one is the one that's surrounded the most.
+                enclosed_by: enclosed_by[label]
+                    .iter()
+                    .max_by_key(|outer| enclosed_by[**outer].len())
+                    .copied(),
+            })
+            .collect()
+    }
+
+    /// Returns the regions inside each hole in the region labeled `label`.
+    ///
+    /// A hole is a group of touching plots outside the region that can't reach the edge of the
+    /// garden without crossing it. Plots outside `bounds` can always reach the edge, so only
+    /// the plots inside it (and the ring of plots just outside it) need to be looked at.
+    fn holes(labels: &[Vec<usize>], label: usize, bounds: &Bounds) -> Vec<Vec<usize>> {
+        // Shifted by one, so the ring around `bounds` starts at zero.
+        let width = bounds.max.0 - bounds.min.0 + 3;
+        let height = bounds.max.1 - bounds.min.1 + 3;
+        // Returns the label of the plot at `(x, y)` (or `None` outside the garden), but only
+        // if it's outside the region.
+        let inside = |(x, y): Coord| {
+            let plot = (x + bounds.min.0).checked_sub(1).zip((y + bounds.min.1).checked_sub(1));
+            let inner = plot.and_then(|(x, y)| labels.get(y)?.get(x).copied());
+            (inner != Some(label)).then_some(inner)
+        };
+
+        let mut seen = vec![vec![false; width]; height];
+        let fill = |start: Coord, seen: &mut [Vec<bool>]| {
+            let mut found = Vec::new();
+            let mut stack = vec![start];
+            seen[start.1][start.0] = true;
+            while let Some(coord) = stack.pop() {
+                // SAFETY: Only plots outside the region are pushed.
+                found.extend(inside(coord).unwrap());
+                for (nx, ny) in Self::neighbours(coord) {
+                    if nx < width && ny < height && !seen[ny][nx] && inside((nx, ny)).is_some() {
+                        seen[ny][nx] = true;
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+            found
+        };
+
+        // Everything reachable from the ring isn't a hole.
+        let ring = (0..width)
+            .flat_map(|x| [(x, 0), (x, height - 1)])
+            .chain((0..height).flat_map(|y| [(0, y), (width - 1, y)]));
+        for coord in ring {
+            if !seen[coord.1][coord.0] {
+                fill(coord, &mut seen);
+            }
+        }
+
+        let mut holes = Vec::new();
+        for y in 1..height - 1 {
+            for x in 1..width - 1 {
+                if !seen[y][x] && inside((x, y)).is_some() {
+                    let mut inner = fill((x, y), &mut seen);
+                    inner.sort_unstable();
+                    inner.dedup();
+                    holes.push(inner);
+                }
+            }
+        }
+        holes
+    }
+
+    /// Draws the region labeled `label` on its own, with its fence around it.
+    ///
+    /// Plots are drawn with a gap between them for the fence. Plots outside the region are
+    /// drawn as `.`, and the fence is drawn with `-` and `|`, with a `+` wherever it turns.
+    /// Every `+` is a corner, so there are as many of them as the region has sides, except
+    /// where two corners touch diagonally and share the same `+`.
+    #[allow(dead_code)]
+    fn render_region(&self, label: usize) -> String {
+        let (labels, _) = self.label();
+        let bounds = Self::bounds_of(&labels, label);
+        let in_region = |x: usize, y: usize| {
+            labels.get(y).and_then(|row| row.get(x)) == Some(&label)
+        };
+        // Whether the plot up and to the left of the point at `(x, y)` is in the region, where
+        // points sit between plots and there's one more of them than there are plots.
+        let before = |x: usize, y: usize| {
+            x.checked_sub(1)
+                .zip(y.checked_sub(1))
+                .is_some_and(|(x, y)| in_region(x, y))
+        };
+
+        let (width, height) = (bounds.max.0 - bounds.min.0 + 1, bounds.max.1 - bounds.min.1 + 1);
+        // Every row has a point on both sides of every plot (`2 * width + 1` characters), plus
+        // a newline, and there's a row of points above and below every row of plots.
+        let mut buf = String::with_capacity((2 * width + 2) * (2 * height + 1));
+        for row in 0..=2 * height {
+            for column in 0..=2 * width {
+                let (x, y) = (bounds.min.0 + column / 2, bounds.min.1 + row / 2);
+                buf.push(match (column % 2, row % 2) {
+                    // A plot.
+                    (1, 1) if in_region(x, y) => self.plants[y][x],
+                    (1, 1) => '.',
+                    // Between two plots above each other.
+                    (1, 0) if before(x + 1, y) != in_region(x, y) => '-',
+                    // Between two plots beside each other.
+                    (0, 1) if before(x, y + 1) != in_region(x, y) => '|',
+                    // A point between four plots.
+                    (0, 0) => {
+                        let (up_left, up_right) = (before(x, y), before(x + 1, y));
+                        let (down_left, down_right) = (before(x, y + 1), in_region(x, y));
+                        let vertical = up_left != up_right || down_left != down_right;
+                        let horizontal = up_left != down_left || up_right != down_right;
+                        match (vertical, horizontal) {
+                            (true, true) => '+',
+                            (true, false) => '|',
+                            (false, true) => '-',
+                            (false, false) => ' ',
+                        }
+                    }
+                    _ => ' ',
+                });
+            }
+            buf.truncate(buf.trim_end_matches(' ').len());
+            buf.push('\n');
+        }
+
+        buf
+    }
+
+    /// Returns the smallest box around the region labeled `label`.
+    fn bounds_of(labels: &[Vec<usize>], label: usize) -> Bounds {
+        let mut plots = labels.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(move |(_, inner)| **inner == label)
+                .map(move |(x, _)| (x, y))
+        });
+        let first = plots.next().expect("There's no region with that label.");
+
+        let mut bounds = Bounds { min: first, max: first };
+        plots.for_each(|plot| bounds.extend(plot));
+        bounds
+    }
+}
+
+/// Measures the regions in a garden one row at a time, without ever holding more than two rows.
+///
+/// Each row is labeled in a single pass: a plot joins the region of the plot to its left or
+/// above it if they grow the same plant, and when it could join both, their regions are merged
+/// with a union-find. Perimeters and corners only depend on which plants grow next to each
+/// other, so they're counted as soon as both rows around them are known.
+///
+/// After every row, the labels are renumbered so that only regions with plots in that row are
+/// kept. Any other region can't grow anymore, so it's done. That keeps memory proportional to
+/// the width of the garden, plus the number of regions found so far.
+#[derive(Debug, Clone, Default)]
+struct RegionStream {
+    /// The index of the next row.
+    y: usize,
+    /// The plants in the previous row.
+    prev: Vec<char>,
+    /// The label of each plot in the previous row.
+    prev_labels: Vec<usize>,
+    sets: RegionSets,
+    /// Every region that's done, along with its first plot.
+    done: Vec<(Coord, Region)>,
+}
+
+impl RegionStream {
+    /// Labels and measures the plots in the next row of the garden.
+    fn push_row(&mut self, row: &[char]) {
+        let (prev, y) = (&self.prev, self.y);
+        let mut labels: Vec<usize> = Vec::with_capacity(row.len());
+        let at = |plants: &[char], x: Option<usize>| x.and_then(|x| plants.get(x).copied());
+
+        for (x, plant) in row.iter().enumerate() {
+            let left = x.checked_sub(1).filter(|x| row[*x] == *plant).map(|x| labels[x]);
+            let up = (prev.get(x) == Some(plant)).then(|| self.prev_labels[x]);
+
+            let label = match (left, up) {
+                (Some(left), Some(up)) => self.sets.union(left, up),
+                (Some(label), None) | (None, Some(label)) => label,
+                (None, None) => self.sets.insert(*plant, (x, y)),
+            };
+            labels.push(label);
+
+            let (left, right) = (at(row, x.checked_sub(1)), at(row, Some(x + 1)));
+            let (up_left, up, up_right) = (
+                at(prev, x.checked_sub(1)),
+                at(prev, Some(x)),
+                at(prev, Some(x + 1)),
+            );
+            let plant = Some(*plant);
+
+            let region = self.sets.region(label);
+            region.area += 1;
+            region.perimeter +=
+                (left != plant) as usize + (right != plant) as usize + (up != plant) as usize;
+            region.sides += is_corner(plant, left, up, up_left) as usize;
+            region.sides += is_corner(plant, right, up, up_right) as usize;
+        }
+
+        // The plots in the previous row can only be measured now that the row below is known.
+        for (x, plant) in prev.iter().enumerate() {
+            let (left, right) = (at(prev, x.checked_sub(1)), at(prev, Some(x + 1)));
+            let (down_left, down, down_right) = (
+                at(row, x.checked_sub(1)),
+                at(row, Some(x)),
+                at(row, Some(x + 1)),
+            );
+            let plant = Some(*plant);
+
+            let region = self.sets.region(self.prev_labels[x]);
+            region.perimeter += (down != plant) as usize;
+            region.sides += is_corner(plant, left, down, down_left) as usize;
+            region.sides += is_corner(plant, right, down, down_right) as usize;
+        }
+
+        let (sets, done) = std::mem::take(&mut self.sets).compact(&mut labels);
+        self.sets = sets;
+        self.done.extend(done);
+        self.prev = row.to_vec();
+        self.prev_labels = labels;
+        self.y += 1;
+    }
+
+    /// Returns every region in the garden, in the order their first plot shows up.
+    fn finish(mut self) -> Vec<Region> {
+        // An empty row after the last one closes the fences along the bottom of the garden.
+        self.push_row(&[]);
+
+        self.done.sort_unstable_by_key(|((x, y), _)| (*y, *x));
+        self.done.into_iter().map(|(_, region)| region).collect()
+    }
+}
+
+/// Returns `true` if a plot growing `plant` has a corner of its region between it and the plots
+/// `across` from it, `vertical` to it, and `diagonal` to it. `None` stands for outside the garden.
+///
+/// See [Garden::measure] for why corners are sides.
+fn is_corner(
+    plant: Option<char>,
+    across: Option<char>,
+    vertical: Option<char>,
+    diagonal: Option<char>,
+) -> bool {
+    let (across, vertical, diagonal) = (across == plant, vertical == plant, diagonal == plant);
+    (!across && !vertical) || (across && vertical && !diagonal)
+}
+
+/// The regions a [RegionStream] is working on, as a union-find over their labels.
+#[derive(Debug, Clone, Default)]
+struct RegionSets {
+    /// The label each label was merged into, or itself if it wasn't.
+    parents: Vec<usize>,
+    /// The region each label stands for, along with its first plot. Only up to date for labels
+    /// that weren't merged into another one.
+    regions: Vec<(Coord, Region)>,
+}
+
+impl RegionSets {
+    /// Starts a new region at `first`, and returns its label.
+    fn insert(&mut self, plant: char, first: Coord) -> usize {
+        let region = Region {
+            plant,
+            area: 0,
+            perimeter: 0,
+            sides: 0,
+        };
+        self.parents.push(self.parents.len());
+        self.regions.push((first, region));
+        self.parents.len() - 1
+    }
+
+    /// Returns the label that `label` was merged into.
+    fn find(&mut self, mut label: usize) -> usize {
+        while self.parents[label] != label {
+            // Skip every other label on the way up, so the next search is shorter.
+            self.parents[label] = self.parents[self.parents[label]];
+            label = self.parents[label];
+        }
+        label
+    }
+
+    /// Merges the regions labeled `a` and `b`, and returns the label of the merged region.
+    fn union(&mut self, a: usize, b: usize) -> usize {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return a;
+        }
+
+        // Keep the label with the earliest first plot, so it stays the first plot.
+        let reading_order = |label: usize| {
+            let (x, y) = self.regions[label].0;
+            (y, x)
+        };
+        let (root, child) = if reading_order(a) < reading_order(b) { (a, b) } else { (b, a) };
+        self.parents[child] = root;
+
+        let child = self.regions[child].1.clone();
+        let region = &mut self.regions[root].1;
+        region.area += child.area;
+        region.perimeter += child.perimeter;
+        region.sides += child.sides;
+        root
+    }
+
+    /// Returns the region labeled `label`.
+    fn region(&mut self, label: usize) -> &mut Region {
+        let label = self.find(label);
+        &mut self.regions[label].1
+    }
+
+    /// Renumbers the regions so that only the ones in `labels` are kept, updating `labels` to
+    /// match, and returns the regions that weren't kept.
+    fn compact(mut self, labels: &mut [usize]) -> (Self, Vec<(Coord, Region)>) {
+        let mut kept = Self::default();
+        let mut renumbered = vec![usize::MAX; self.parents.len()];
+
+        for label in labels.iter_mut() {
+            let root = self.find(*label);
+            if renumbered[root] == usize::MAX {
+                renumbered[root] = kept.parents.len();
+                kept.parents.push(kept.parents.len());
+                kept.regions.push(self.regions[root].clone());
+            }
+            *label = renumbered[root];
+        }
+
+        let done = (0..self.parents.len())
+            .filter(|label| self.parents[*label] == *label && renumbered[*label] == usize::MAX)
+            .map(|label| self.regions[label].clone())
+            .collect();
+        (kept, done)
+    }
+}
+
+/// The smallest box around a region, with both corners inside the box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Bounds {
+    min: Coord,
+    max: Coord,
+}
+
+impl Bounds {
+    /// Grows the box so that `(x, y)` is inside it.
+    fn extend(&mut self, (x, y): Coord) {
+        self.min = (self.min.0.min(x), self.min.1.min(y));
+        self.max = (self.max.0.max(x), self.max.1.max(y));
+    }
+}
+
+/// Everything [Garden::analyze] found out about a region.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RegionReport {
+    region: Region,
+    bounds: Bounds,
+    /// The number of separate holes in the region, each with one or more regions inside it.
+    /// Every hole has its own fence, so its sides count towards the region's sides.
+    holes: usize,
+    /// The innermost region that completely surrounds this one, if any.
+    enclosed_by: Option<usize>,
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lcg::Lcg;
+
+    const EXAMPLE: &str = "\
+RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE";
+
+    const SMALL_EXAMPLE: &str = "\
+AAAA
+BBCD
+BBCC
+EEEC";
+
+    const NESTED_EXAMPLE: &str = "\
+OOOOO
+OXOXO
+OOOOO
+OXOXO
+OOOOO";
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse("<EXAMPLE>")), "<RESULT>");
+        assert_eq!(part1(&parse(SMALL_EXAMPLE)), 140);
+        assert_eq!(part1(&parse(NESTED_EXAMPLE)), 772);
+        assert_eq!(part1(&parse(EXAMPLE)), 1930);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse("<EXAMPLE>")), "<RESULT>");
+        assert_eq!(part2(&parse(SMALL_EXAMPLE)), 80);
+        assert_eq!(part2(&parse(NESTED_EXAMPLE)), 436);
+        assert_eq!(part2(&parse(EXAMPLE)), 1206);
+        assert_eq!(part2(&parse("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE")), 236);
+        assert_eq!(part2(&parse("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA")), 368);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn regions_example() {
+        let regions = parse(SMALL_EXAMPLE).regions();
+        let summary: Vec<_> = regions
+            .iter()
+            .map(|region| (region.plant, region.area, region.perimeter, region.sides))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ('A', 4, 10, 4),
+                ('B', 4, 8, 4),
+                ('C', 4, 10, 8),
+                ('D', 1, 4, 4),
+                ('E', 3, 8, 4)
+            ]
+        );
+
+        // The same plant can grow in regions that don't touch.
+        let regions = parse(NESTED_EXAMPLE).regions();
+        assert_eq!(regions.len(), 5);
+        assert_eq!(regions[0].sides, 20);
+        assert!(regions[1..].iter().all(|region| region.plant == 'X' && region.sides == 4));
+    }
+
+    #[test]
+    fn analyze_nested_regions() {
+        let reports = parse(NESTED_EXAMPLE).analyze();
+        assert_eq!(reports[0].holes, 4);
+        assert_eq!(reports[0].enclosed_by, None);
+        assert_eq!(reports[0].bounds, Bounds { min: (0, 0), max: (4, 4) });
+        for report in &reports[1..] {
+            assert_eq!(report.holes, 0);
+            assert_eq!(report.enclosed_by, Some(0));
+            assert_eq!(report.bounds.min, report.bounds.max);
+        }
+
+        let reports = parse("AAAAA\nABBBA\nABCBA\nABBBA\nAAAAA").analyze();
+        let summary: Vec<_> = reports
+            .iter()
+            .map(|report| (report.region.sides, report.holes, report.enclosed_by))
+            .collect();
+        // The `B` region and the `C` region inside it make one hole in the `A` region.
+        assert_eq!(summary, [(8, 1, None), (8, 1, Some(0)), (4, 0, Some(1))]);
+
+        // The `B` regions only touch diagonally, so they're two separate holes.
+        let reports = parse("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA").analyze();
+        assert_eq!(reports[0].holes, 2);
+        assert_eq!(reports[0].region.sides, 12);
+        assert_eq!(reports[1].enclosed_by, Some(0));
+        assert_eq!(reports[2].enclosed_by, Some(0));
+        assert_eq!(reports[2].bounds, Bounds { min: (1, 3), max: (2, 4) });
+
+        // Regions on the edge of the garden are never enclosed.
+        let reports = parse(EXAMPLE).analyze();
+        assert!(reports.iter().all(|report| report.holes == 0 && report.enclosed_by.is_none()));
+    }
+
+    #[test]
+    fn render_regions() {
+        let garden = parse("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE");
+        assert_eq!(
+            garden.render_region(0),
+            "\
++---------+
+|E E E E E|
+| +-------+
+|E|. . . .
+| +-------+
+|E E E E E|
+| +-------+
+|E|. . . .
+| +-------+
+|E E E E E|
++---------+
+"
+        );
+        assert_eq!(garden.render_region(1), "+-------+\n|X X X X|\n+-------+\n");
+
+        // The corners where the `B` regions touch share a `+`.
+        let garden = parse("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA");
+        assert_eq!(
+            garden.render_region(0),
+            "\
++-----------+
+|A A A A A A|
+|     +---+ |
+|A A A|. .|A|
+|     |   | |
+|A A A|. .|A|
+| +---+---+ |
+|A|. .|A A A|
+| |   |     |
+|A|. .|A A A|
+| +---+     |
+|A A A A A A|
++-----------+
+"
+        );
+    }
+
+    #[test]
+    fn streaming_matches_flood_fill() {
+        for (size, plants) in [(8, 2), (30, 3), (60, 4), (100, 2)] {
+            let garden = synthetic_garden(size, plants);
+            let (labels, count) = garden.label();
+            let expected = garden.measure(&labels, count);
+            assert_eq!(garden.regions(), expected, "{size}x{size}, {plants} plants");
+        }
+    }
+
+    #[test]
+    fn streaming_huge_garden() {
+        // A 5000x5000 garden with one `A` region snaking through it from top to bottom, and a
+        // `B` region for every row it doesn't fill.
+        const SIZE: usize = 5000;
+        let rows: Vec<Vec<char>> = (0..4)
+            .map(|y| {
+                (0..SIZE)
+                    .map(|x| match y {
+                        0 | 2 => 'A',
+                        1 if x == SIZE - 1 => 'A',
+                        3 if x == 0 => 'A',
+                        _ => 'B',
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut stream = RegionStream::default();
+        for y in 0..SIZE {
+            stream.push_row(&rows[y % 4]);
+        }
+        let regions = stream.finish();
+
+        assert_eq!(regions.len(), 1 + SIZE / 2);
+        assert_eq!(regions[0].plant, 'A');
+        assert_eq!(regions[0].area, SIZE * SIZE - SIZE / 2 * (SIZE - 1));
+        for region in &regions[1..] {
+            assert_eq!(region.plant, 'B');
+            assert_eq!((region.area, region.perimeter, region.sides), (SIZE - 1, 2 * SIZE, 4));
+        }
+    }
+
+    /// Returns a `size`x`size` garden with up to `plants` types of plant, in patches.
+    fn synthetic_garden(size: usize, plants: u64) -> Garden {
+        let mut lcg = Lcg::new(0x2024_0012);
+        let mut plants_at = vec![vec!['A'; size]; size];
+        for y in 0..size {
+            for x in 0..size {
+                let seed = lcg.next();
+                // Copy a neighbour most of the time, so regions grow bigger than a plot or two.
+                plants_at[y][x] = match (seed >> 33) % 4 {
+                    0 if x > 0 => plants_at[y][x - 1],
+                    1 if y > 0 => plants_at[y - 1][x],
+                    _ => (b'A' + ((seed >> 40) % plants) as u8) as char,
+                };
+            }
+        }
+        Garden { plants: plants_at }
+    }
+
+    #[test]
+    fn diagonal_plots_are_separate_regions() {
+        // The two `B` regions only touch at a corner, which counts as a corner for both.
+        let regions = parse("AB\nBA").regions();
+        assert_eq!(regions.len(), 4);
+        assert!(regions.iter().all(|region| region.sides == 4));
+    }
+}
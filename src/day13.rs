@@ -1,31 +1,412 @@
 use aoc_runner_derive::{aoc, aoc_generator};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{i64, line_ending, multispace0, multispace1},
+    combinator::{all_consuming, map},
+    multi::separated_list1,
+    sequence::{preceded, separated_pair, terminated, tuple},
+    IResult,
+};
+
 #[aoc_generator(day13)]
-fn parse(_input: &str) -> String {
-    todo!()
+fn parse(input: &str) -> Vec<Machine> {
+    let (_, machines) = all_consuming(terminated(
+        separated_list1(multispace1, machine),
+        multispace0,
+    ))(input)
+    .expect("That wasn't a list of claw machines.");
+    machines
+}
+
+/// Parses a single claw machine, like:
+///
+/// ```text
+/// Button A: X+94, Y+34
+/// Button B: X+22, Y+67
+/// Prize: X=8400, Y=5400
+/// ```
+fn machine(input: &str) -> IResult<&str, Machine> {
+    let offset = |prefix| {
+        preceded(
+            tag(prefix),
+            separated_pair(preceded(tag("X+"), i64), tag(", "), preceded(tag("Y+"), i64)),
+        )
+    };
+    let prize = preceded(
+        tag("Prize: "),
+        separated_pair(preceded(tag("X="), i64), tag(", "), preceded(tag("Y="), i64)),
+    );
+
+    map(
+        tuple((
+            terminated(offset("Button A: "), line_ending),
+            terminated(offset("Button B: "), line_ending),
+            prize,
+        )),
+        |(a, b, prize)| Machine { a, b, prize },
+    )(input)
 }
 
 #[aoc(day13, part1)]
-fn part1(_input: &str) -> String {
-    todo!()
+fn part1(machines: &[Machine]) -> u64 {
+    machines
+        .iter()
+        .filter_map(|machine| machine.cheapest(Some(PART1_PRESS_LIMIT)))
+        .map(|presses| presses.cost())
+        .sum()
 }
 
 #[aoc(day13, part2)]
-fn part2(_input: &str) -> String {
-    todo!()
+fn part2(machines: &[Machine]) -> u64 {
+    machines
+        .iter()
+        .filter_map(|machine| machine.with_prize_offset(PART2_PRIZE_OFFSET).cheapest(None))
+        .map(|presses| presses.cost())
+        .sum()
+}
+
+/// The most times each button can be pressed in part one.
+const PART1_PRESS_LIMIT: u64 = 100;
+
+/// How much further away every prize really is in part two, along both axes.
+const PART2_PRIZE_OFFSET: i64 = 10_000_000_000_000;
+
+/// The number of tokens it takes to push the A button.
+const A_COST: u64 = 3;
+
+/// The number of tokens it takes to push the B button.
+const B_COST: u64 = 1;
+
+/// Represents a `(x, y)` pair, for positions and for how far a button moves the claw.
+type Offset = (i64, i64);
+
+/// A claw machine, with its two buttons and the position of its prize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Machine {
+    /// How far the claw moves when the A button is pushed.
+    a: Offset,
+    /// How far the claw moves when the B button is pushed.
+    b: Offset,
+    prize: Offset,
+}
+
+/// How many times each button is pushed to win a prize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Presses {
+    a: u64,
+    b: u64,
+}
+
+impl Presses {
+    /// Returns the number of tokens it takes to push the buttons this many times.
+    fn cost(&self) -> u64 {
+        A_COST * self.a + B_COST * self.b
+    }
+}
+
+impl Machine {
+    /// Returns the same machine, but with its prize `offset` further away along both axes.
+    fn with_prize_offset(&self, offset: i64) -> Self {
+        Self {
+            prize: (self.prize.0 + offset, self.prize.1 + offset),
+            ..*self
+        }
+    }
+
+    /// Returns `true` if both buttons move the claw along the same line (or don't move it at
+    /// all), so there can be more than one way to win the prize.
+    fn is_degenerate(&self) -> bool {
+        (self.a.0 as i128) * (self.b.1 as i128) == (self.a.1 as i128) * (self.b.0 as i128)
+    }
+
+    /// Returns the cheapest way to push the buttons to move the claw right onto the prize,
+    /// pushing each button at most `limit` times if given, or `None` if the prize can't be won.
+    fn cheapest(&self, limit: Option<u64>) -> Option<Presses> {
+        let presses = if self.is_degenerate() {
+            self.cheapest_degenerate(limit.map(i128::from))?
+        } else {
+            self.presses()?
+        };
+
+        match limit {
+            Some(limit) if presses.a > limit || presses.b > limit => None,
+            _ => Some(presses),
+        }
+    }
+
+    /// Returns the only way to push the buttons to move the claw right onto the prize, or
+    /// `None` if the prize can't be won or the machine is degenerate.
+    ///
+    /// The presses solve `a * A + b * B = prize`, two equations with two unknowns, so Cramer's
+    /// rule gives the only solution when the buttons don't move the claw in the same direction.
+    /// It's only a solution if both divisions come out even and neither count is negative.
+    /// Everything's done in `i128`, since the products overflow `i64` in part two.
+    fn presses(&self) -> Option<Presses> {
+        let (ax, ay) = (self.a.0 as i128, self.a.1 as i128);
+        let (bx, by) = (self.b.0 as i128, self.b.1 as i128);
+        let (px, py) = (self.prize.0 as i128, self.prize.1 as i128);
+
+        let determinant = ax * by - ay * bx;
+        if determinant == 0 {
+            return None;
+        }
+
+        let (a, b) = (px * by - py * bx, ax * py - ay * px);
+        if a % determinant != 0 || b % determinant != 0 {
+            return None;
+        }
+
+        Some(Presses {
+            a: u64::try_from(a / determinant).ok()?,
+            b: u64::try_from(b / determinant).ok()?,
+        })
+    }
+
+    /// Returns the cheapest way to win the prize on a degenerate machine.
+    ///
+    /// Both buttons move the claw some number of steps along the same line, so the prize has
+    /// to be on that line too, some whole number of steps away. That leaves one equation,
+    /// `a * steps(A) + b * steps(B) = steps(prize)`, which the extended Euclidean algorithm
+    /// solves for every possible `a` and `b` at once. The cost changes by the same amount
+    /// from one solution to the next, so the cheapest one is at one end of the solutions
+    /// that don't push a button a negative number of times (or more than `limit` times).
+    fn cheapest_degenerate(&self, limit: Option<i128>) -> Option<Presses> {
+        let offsets = [self.a, self.b, self.prize].map(|(x, y)| (x as i128, y as i128));
+        let Some(&(dx, dy)) = offsets[..2].iter().find(|offset| **offset != (0, 0)) else {
+            // Neither button moves the claw, so it has to start on the prize.
+            return (self.prize == (0, 0)).then_some(Presses { a: 0, b: 0 });
+        };
+
+        // The smallest step along the line the claw can move on.
+        let divisor = gcd(dx, dy);
+        let (dx, dy) = (dx / divisor, dy / divisor);
+        let steps = |(x, y): (i128, i128)| {
+            let steps = if dx != 0 { x / dx } else { y / dy };
+            (steps * dx == x && steps * dy == y).then_some(steps)
+        };
+        let [a, b, prize] = offsets.map(steps);
+        // SAFETY: The buttons move the claw along the line.
+        let (a, b, prize) = (a.unwrap(), b.unwrap(), prize?);
+
+        // Every solution is `(x + n * b / g, y - n * a / g)` for some whole number `n`.
+        let (g, x, y) = extended_gcd(a, b);
+        if prize % g != 0 {
+            return None;
+        }
+        let (x, y) = (x * (prize / g), y * (prize / g));
+        let (step_a, step_b) = (b / g, -a / g);
+
+        // The range of `n` where `value + n * step` is between zero and `limit`.
+        let (mut low, mut high) = (None::<i128>, None::<i128>);
+        let mut bounds = vec![(x, step_a), (y, step_b)];
+        if let Some(limit) = limit {
+            bounds.extend([(limit - x, -step_a), (limit - y, -step_b)]);
+        }
+        for (value, step) in bounds {
+            match step.signum() {
+                1 => low = Some(low.unwrap_or(i128::MIN).max(ceil_div(-value, step))),
+                -1 => high = Some(high.unwrap_or(i128::MAX).min(value.div_euclid(-step))),
+                _ if value < 0 => return None,
+                _ => {}
+            }
+        }
+        if let (Some(low), Some(high)) = (low, high) {
+            if low > high {
+                return None;
+            }
+        }
+
+        // If the cost doesn't change from one solution to the next, any of them will do.
+        let cost = A_COST as i128 * step_a + B_COST as i128 * step_b;
+        let n = match cost.signum() {
+            -1 => high,
+            _ => low.or(high),
+        }
+        .expect("The cost never goes below zero, so it can't keep going down forever.");
+
+        Some(Presses {
+            a: u64::try_from(x + n * step_a).ok()?,
+            b: u64::try_from(y + n * step_b).ok()?,
+        })
+    }
 }
 
+/// Returns the indices of the degenerate machines (see [Machine::is_degenerate]).
+#[allow(dead_code)]
+fn degenerate(machines: &[Machine]) -> Vec<usize> {
+    (0..machines.len())
+        .filter(|idx| machines[*idx].is_degenerate())
+        .collect()
+}
+
+/// Returns the greatest common divisor of `a` and `b`, which is never negative.
+fn gcd(a: i128, b: i128) -> i128 {
+    extended_gcd(a, b).0
+}
+
+/// Returns `(g, x, y)`, where `g` is the greatest common divisor of `a` and `b` (never
+/// negative) and `a * x + b * y = g`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// Returns `a / b` rounded up, where `b` is positive.
+fn ceil_div(a: i128, b: i128) -> i128 {
+    -((-a).div_euclid(b))
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "\
+Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+26, Y+66
+Button B: X+67, Y+21
+Prize: X=12748, Y=12176
+
+Button A: X+17, Y+86
+Button B: X+84, Y+37
+Prize: X=7870, Y=6450
+
+Button A: X+69, Y+23
+Button B: X+27, Y+71
+Prize: X=18641, Y=10279
+";
+
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse("<EXAMPLE>")), "<RESULT>");
+        assert_eq!(part1(&parse(EXAMPLE)), 480);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse("<EXAMPLE>")), "<RESULT>");
+        assert_eq!(part2(&parse(EXAMPLE)), 875318608908);
+    }
+
+    #[test]
+    fn parse_example() {
+        let machines = parse(EXAMPLE);
+        assert_eq!(machines.len(), 4);
+        assert_eq!(
+            machines[0],
+            Machine {
+                a: (94, 34),
+                b: (22, 67),
+                prize: (8400, 5400),
+            }
+        );
+    }
+
+    #[test]
+    fn presses_example() {
+        let presses: Vec<_> = parse(EXAMPLE).iter().map(Machine::presses).collect();
+        assert_eq!(
+            presses,
+            [
+                Some(Presses { a: 80, b: 40 }),
+                None,
+                Some(Presses { a: 38, b: 86 }),
+                None
+            ]
+        );
+        assert_eq!(presses[0].unwrap().cost(), 280);
+        assert_eq!(presses[2].unwrap().cost(), 200);
+
+        // Only the second and fourth machines can be won once the prizes move.
+        let presses: Vec<_> = parse(EXAMPLE)
+            .iter()
+            .map(|machine| machine.with_prize_offset(PART2_PRIZE_OFFSET).presses().is_some())
+            .collect();
+        assert_eq!(presses, [false, true, false, true]);
+    }
+
+    #[test]
+    fn degenerate_machines() {
+        let machine = |a, b, prize| Machine { a, b, prize };
+        let cost = |machine: Machine, limit| machine.cheapest(limit).map(|presses| presses.cost());
+
+        assert!(degenerate(&parse(EXAMPLE)).is_empty());
+        let machines = [
+            machine((94, 34), (22, 67), (8400, 5400)),
+            machine((1, 1), (2, 2), (10, 10)),
+            machine((0, 0), (3, 5), (6, 10)),
+        ];
+        assert_eq!(degenerate(&machines), [1, 2]);
+
+        // B moves twice as far for a third of the cost, so it's best to only push B.
+        assert_eq!(machines[1].cheapest(None), Some(Presses { a: 0, b: 5 }));
+        assert_eq!(machines[2].cheapest(None), Some(Presses { a: 0, b: 2 }));
+        // Unless B can't be pushed enough times on its own.
+        let far = machine((1, 1), (2, 2), (300, 300));
+        assert_eq!(far.cheapest(Some(100)), Some(Presses { a: 100, b: 100 }));
+        assert_eq!(far.cheapest(Some(50)), None);
+
+        // There's only one way to move 7 steps with steps of 2 and 3 here.
+        let steps = machine((2, 4), (3, 6), (7, 14));
+        assert_eq!(steps.cheapest(None), Some(Presses { a: 2, b: 1 }));
+        // Off the line, or not a whole number of steps along it.
+        assert_eq!(cost(machine((2, 4), (3, 6), (7, 15)), None), None);
+        assert_eq!(cost(machine((2, 0), (4, 0), (3, 0)), None), None);
+
+        // Every way of pushing 3-step A and 1-step B to move 6 steps costs the same.
+        assert_eq!(cost(machine((3, 3), (1, 1), (6, 6)), None), Some(6));
+        // The buttons move the claw in opposite directions, so there's no upper bound.
+        assert_eq!(machine((1, 0), (-1, 0), (5, 0)).cheapest(None), Some(Presses { a: 5, b: 0 }));
+        // Neither button moves the claw.
+        assert_eq!(cost(machine((0, 0), (0, 0), (0, 0)), None), Some(0));
+        assert_eq!(cost(machine((0, 0), (0, 0), (1, 0)), None), None);
+    }
+
+    #[test]
+    fn degenerate_matches_brute_force() {
+        const LIMIT: u64 = 30;
+        for a in -5..=5 {
+            for b in -5..=5 {
+                for prize in -20..=40 {
+                    let machine = Machine {
+                        a: (a, 2 * a),
+                        b: (b, 2 * b),
+                        prize: (prize, 2 * prize),
+                    };
+                    let expected = (0..=LIMIT)
+                        .flat_map(|a| (0..=LIMIT).map(move |b| Presses { a, b }))
+                        .filter(|presses| presses.a as i64 * a + presses.b as i64 * b == prize)
+                        .map(|presses| presses.cost())
+                        .min();
+
+                    let cost = machine.cheapest(Some(LIMIT)).map(|presses| presses.cost());
+                    assert_eq!(cost, expected, "{machine:?}");
+                }
+            }
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn negative_presses() {
+        // Reaching the prize would take pushing A a negative number of times.
+        let machine = Machine {
+            a: (1, 0),
+            b: (0, 1),
+            prize: (-5, 5),
+        };
+        assert_eq!(machine.presses(), None);
+    }
+}
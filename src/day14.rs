@@ -1,31 +1,658 @@
+use std::io::Write;
+
 use aoc_runner_derive::{aoc, aoc_generator};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{i64, line_ending, multispace0, space1},
+    combinator::{all_consuming, map},
+    multi::separated_list1,
+    sequence::{preceded, separated_pair, terminated},
+    IResult,
+};
+
 #[aoc_generator(day14)]
-fn parse(_input: &str) -> String {
-    todo!()
+fn parse(input: &str) -> Vec<Robot> {
+    let (_, robots) = all_consuming(terminated(
+        separated_list1(line_ending, robot),
+        multispace0,
+    ))(input)
+    .expect("That wasn't a list of robots.");
+    robots
+}
+
+/// Parses a single robot, like `p=0,4 v=3,-3`.
+fn robot(input: &str) -> IResult<&str, Robot> {
+    let pair = || separated_pair(i64, tag(","), i64);
+    map(
+        separated_pair(preceded(tag("p="), pair()), space1, preceded(tag("v="), pair())),
+        |(position, velocity)| Robot { position, velocity },
+    )(input)
 }
 
 #[aoc(day14, part1)]
-fn part1(_input: &str) -> String {
-    todo!()
+fn part1(robots: &[Robot]) -> usize {
+    REAL_ROOM.safety_factor(&REAL_ROOM.simulate(robots, PART1_SECONDS))
 }
 
 #[aoc(day14, part2)]
-fn part2(_input: &str) -> String {
-    todo!()
+fn part2(robots: &[Robot]) -> i64 {
+    REAL_ROOM
+        .find_tree(robots)
+        .expect("The room's width and height should have nothing in common.")
+}
+
+/// The number of seconds the robots move for in part one.
+const PART1_SECONDS: i64 = 100;
+
+/// The size of the room outside the bathroom.
+const REAL_ROOM: Room = Room {
+    width: 101,
+    height: 103,
+};
+
+/// Represents a `(x, y)` pair, for positions and velocities.
+type Coord = (i64, i64);
+
+/// A security robot, patrolling the room in a straight line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Robot {
+    position: Coord,
+    /// How far the robot moves every second.
+    velocity: Coord,
+}
+
+/// The room the robots patrol. Robots that walk off one edge teleport to the other side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Room {
+    width: i64,
+    height: i64,
+}
+
+impl Room {
+    /// Returns where `robot` is after `seconds` seconds.
+    ///
+    /// Every robot is back where it started after `width` seconds along x and `height`
+    /// seconds along y, so only the remainders matter, no matter how many seconds pass.
+    fn position_after(&self, robot: &Robot, seconds: i64) -> Coord {
+        let (x, y) = robot.position;
+        let (dx, dy) = robot.velocity;
+        (
+            (x + dx * seconds.rem_euclid(self.width)).rem_euclid(self.width),
+            (y + dy * seconds.rem_euclid(self.height)).rem_euclid(self.height),
+        )
+    }
+
+    /// Returns where every robot is after `seconds` seconds.
+    fn simulate(&self, robots: &[Robot], seconds: i64) -> Vec<Coord> {
+        robots
+            .iter()
+            .map(|robot| self.position_after(robot, seconds))
+            .collect()
+    }
+
+    /// Returns the safety factor for robots at `positions`: the number of robots in each
+    /// quadrant of the room, multiplied together. Robots exactly in the middle of the room
+    /// (horizontally or vertically) aren't in any quadrant.
+    fn safety_factor(&self, positions: &[Coord]) -> usize {
+        let (middle_x, middle_y) = (self.width / 2, self.height / 2);
+        let mut quadrants = [0; 4];
+
+        for (x, y) in positions {
+            // With an even size there's no middle: `size / 2` is the first column (or row)
+            // of the second half.
+            let left = *x < middle_x;
+            let right = *x > middle_x || (*x == middle_x && self.width % 2 == 0);
+            let top = *y < middle_y;
+            let bottom = *y > middle_y || (*y == middle_y && self.height % 2 == 0);
+
+            match (left, right, top, bottom) {
+                (true, _, true, _) => quadrants[0] += 1,
+                (_, true, true, _) => quadrants[1] += 1,
+                (true, _, _, true) => quadrants[2] += 1,
+                (_, true, _, true) => quadrants[3] += 1,
+                _ => {}
+            }
+        }
+
+        quadrants.iter().product()
+    }
+
+    /// Returns the number of robots on each position in the room, in reading order.
+    fn robots_on(&self, positions: &[Coord]) -> Vec<u16> {
+        let mut robots = vec![0; (self.width * self.height) as usize];
+        for (x, y) in positions {
+            robots[(y * self.width + x) as usize] += 1;
+        }
+        robots
+    }
+
+    /// Draws the robots at `positions` the way the puzzle does: the number of robots on each
+    /// position, or `.` if there aren't any. Positions with more than 9 robots are drawn as `+`.
+    #[allow(dead_code)]
+    fn render(&self, positions: &[Coord]) -> String {
+        // The `+1` here is for the newlines at the end of each row.
+        let mut buf = String::with_capacity(((self.width + 1) * self.height) as usize);
+        for row in self.robots_on(positions).chunks(self.width as usize) {
+            for robots in row {
+                buf.push(match robots {
+                    0 => '.',
+                    1..=9 => char::from_digit(*robots as u32, 10).unwrap(),
+                    _ => '+',
+                });
+            }
+            buf.push('\n');
+        }
+
+        buf
+    }
+
+    /// Returns the number of seconds it takes for every robot to be back where it started.
+    fn period(&self) -> i64 {
+        self.width / gcd(self.width, self.height) * self.height
+    }
+
+    /// Scores every frame in a full period with `heuristic`, and returns the `count` best ones,
+    /// best first. Frames with the same score are sorted by when they happen.
+    #[allow(dead_code)]
+    fn candidates(&self, robots: &[Robot], heuristic: Heuristic, count: usize) -> Vec<Candidate> {
+        let mut candidates: Vec<Candidate> = (0..self.period())
+            .map(|seconds| Candidate {
+                seconds,
+                score: heuristic.score(self, &self.simulate(robots, seconds)),
+            })
+            .collect();
+
+        candidates.sort_unstable_by_key(|candidate| (-candidate.score, candidate.seconds));
+        candidates.truncate(count);
+        candidates
+    }
+
+    /// Returns the first second the robots form a picture, without looking at every frame.
+    ///
+    /// The robots bunch up to draw the picture, so that's when their positions vary the least.
+    /// Along x, the positions repeat every `width` seconds, so the least varied x positions
+    /// can be found by only looking at the first `width` seconds. Same goes for y. The
+    /// picture shows up when both happen at once, which the Chinese remainder theorem finds.
+    ///
+    /// Returns `None` if the width and height have a common divisor, since then there might
+    /// be no such second.
+    fn find_tree(&self, robots: &[Robot]) -> Option<i64> {
+        let quietest = |size: i64, axis: fn(Coord) -> i64| {
+            (0..size)
+                .min_by_key(|seconds| {
+                    variance(self.simulate(robots, *seconds).into_iter().map(axis))
+                })
+                .unwrap_or(0)
+        };
+
+        let x = quietest(self.width, |(x, _)| x);
+        let y = quietest(self.height, |(_, y)| y);
+        crt((x, self.width), (y, self.height))
+    }
+}
+
+/// A way of scoring how much the robots look like a picture. Higher scores look more like one.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Heuristic {
+    /// How little the robots' positions vary, as the negated sum of both axes' variances.
+    Variance,
+    /// The number of robots in the largest group of robots next to each other (no diagonals).
+    /// Robots on the same position only count once.
+    LargestComponent,
+    /// The number of positions with a robot on them, which is highest when no robots overlap.
+    NoOverlaps,
+}
+
+#[allow(dead_code)]
+impl Heuristic {
+    /// Returns the score for robots at `positions` in `room`.
+    fn score(&self, room: &Room, positions: &[Coord]) -> i64 {
+        match self {
+            Self::Variance => {
+                let xs = positions.iter().map(|(x, _)| *x);
+                let ys = positions.iter().map(|(_, y)| *y);
+                -(variance(xs) + variance(ys))
+            }
+            Self::LargestComponent => {
+                let mut unseen = room.robots_on(positions);
+                let mut largest = 0;
+                let mut stack = Vec::new();
+
+                for start in positions {
+                    if unseen[(start.1 * room.width + start.0) as usize] == 0 {
+                        continue;
+                    }
+
+                    unseen[(start.1 * room.width + start.0) as usize] = 0;
+                    stack.push(*start);
+                    let mut size = 0;
+                    while let Some((x, y)) = stack.pop() {
+                        size += 1;
+                        for (x, y) in [(x, y - 1), (x + 1, y), (x, y + 1), (x - 1, y)] {
+                            if !(0..room.width).contains(&x) || !(0..room.height).contains(&y) {
+                                continue;
+                            }
+                            let idx = (y * room.width + x) as usize;
+                            if unseen[idx] != 0 {
+                                unseen[idx] = 0;
+                                stack.push((x, y));
+                            }
+                        }
+                    }
+                    largest = largest.max(size);
+                }
+
+                largest
+            }
+            Self::NoOverlaps => {
+                let robots = room.robots_on(positions);
+                robots.iter().filter(|robots| **robots != 0).count() as i64
+            }
+        }
+    }
+}
+
+/// A frame that might show a picture, and how much it looks like one (see [Heuristic]).
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Candidate {
+    seconds: i64,
+    score: i64,
+}
+
+/// The image formats frames can be written in. Both are the plain (text) variants of the
+/// Netpbm formats, which most image viewers can open.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImageFormat {
+    /// Black and white: black wherever there's at least one robot.
+    Pbm,
+    /// Grayscale: the more robots on a position, the brighter it is.
+    Pgm,
+}
+
+impl ImageFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Pbm => "pbm",
+            Self::Pgm => "pgm",
+        }
+    }
+}
+
+/// Returns an image of the robots at `positions` in `room`, in `format`.
+fn frame_to_bytes(room: &Room, positions: &[Coord], format: ImageFormat) -> Vec<u8> {
+    let robots = room.robots_on(positions);
+    let mut buf = match format {
+        ImageFormat::Pbm => format!("P1\n{} {}\n", room.width, room.height),
+        ImageFormat::Pgm => {
+            // The brightest gray is the most robots on any one position.
+            let brightest = robots.iter().max().copied().unwrap_or(0).max(1);
+            format!("P2\n{} {}\n{brightest}\n", room.width, room.height)
+        }
+    };
+
+    // The plain formats don't allow lines longer than this, so long rows are wrapped.
+    const MAX_LINE_LEN: usize = 70;
+
+    for row in robots.chunks(room.width as usize) {
+        let mut line_len = 0;
+        for robots in row {
+            let pixel = match format {
+                ImageFormat::Pbm => u16::from(*robots > 0).to_string(),
+                ImageFormat::Pgm => robots.to_string(),
+            };
+
+            if line_len == 0 {
+                line_len = pixel.len();
+            } else if line_len + 1 + pixel.len() > MAX_LINE_LEN {
+                buf.push('\n');
+                line_len = pixel.len();
+            } else {
+                buf.push(' ');
+                line_len += 1 + pixel.len();
+            }
+            buf.push_str(&pixel);
+        }
+        buf.push('\n');
+    }
+
+    buf.into_bytes()
+}
+
+/// Writes an image of the robots at `positions` in `room` to the file at `path`.
+fn write_frame_to(
+    room: &Room,
+    positions: &[Coord],
+    format: ImageFormat,
+    path: &str,
+) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    file.write_all(&frame_to_bytes(room, positions, format))
 }
 
+/// Writes an image of the robots after each of `seconds` into the directory `dir`, using
+/// [write_frame_to]. Each frame is named after its second (`frame00000.pgm`, ...).
+///
+/// Returns the number of frames written.
+#[allow(dead_code)]
+fn write_frames_to(
+    room: &Room,
+    robots: &[Robot],
+    seconds: impl IntoIterator<Item = i64>,
+    format: ImageFormat,
+    dir: &str,
+) -> std::io::Result<usize> {
+    std::fs::create_dir_all(dir)?;
+
+    let mut frames = 0usize;
+    for seconds in seconds {
+        let path = format!("{dir}/frame{seconds:05}.{}", format.extension());
+        write_frame_to(room, &room.simulate(robots, seconds), format, &path)?;
+        frames += 1;
+    }
+
+    Ok(frames)
+}
+
+/// Writes the `count` frames that look the most like a picture according to `heuristic` into
+/// the directory `dir`, using [write_frames_to]. Returns the candidates that were written, best
+/// first.
+#[allow(dead_code)]
+fn write_top_frames_to(
+    room: &Room,
+    robots: &[Robot],
+    heuristic: Heuristic,
+    count: usize,
+    format: ImageFormat,
+    dir: &str,
+) -> std::io::Result<Vec<Candidate>> {
+    let candidates = room.candidates(robots, heuristic, count);
+    let seconds = candidates.iter().map(|candidate| candidate.seconds);
+    write_frames_to(room, robots, seconds, format, dir)?;
+    Ok(candidates)
+}
+
+/// Returns the variance of `values`, times the number of values squared so it stays a whole
+/// number. That's just as good for comparing sets of values of the same size.
+fn variance(values: impl Iterator<Item = i64>) -> i64 {
+    let (mut count, mut sum, mut squares) = (0, 0, 0);
+    for value in values {
+        count += 1;
+        sum += value;
+        squares += value * value;
+    }
+    count * squares - sum * sum
+}
+
+/// Returns the greatest common divisor of `a` and `b`.
+fn gcd(mut a: i64, mut b: i64) -> i64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Returns the smallest non-negative `n` where `n % a_modulus == a` and `n % b_modulus == b`,
+/// or `None` if the moduli aren't coprime.
+fn crt((a, a_modulus): (i64, i64), (b, b_modulus): (i64, i64)) -> Option<i64> {
+    if gcd(a_modulus, b_modulus) != 1 {
+        return None;
+    }
+
+    // Count up from `a` in steps of `a_modulus` until `b` comes up. `b_modulus` steps is
+    // always enough, and the moduli are small enough here that there's no need for anything
+    // faster.
+    (0..b_modulus)
+        .map(|step| a + step * a_modulus)
+        .find(|n| n.rem_euclid(b_modulus) == b.rem_euclid(b_modulus))
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{lcg::Lcg, temp_dir::TempDir};
+
+    const EXAMPLE: &str = "\
+p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3";
+
+    /// The size of the room in the puzzle's examples.
+    const EXAMPLE_ROOM: Room = Room {
+        width: 11,
+        height: 7,
+    };
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse("<EXAMPLE>")), "<RESULT>");
+        let positions = EXAMPLE_ROOM.simulate(&parse(EXAMPLE), PART1_SECONDS);
+        assert_eq!(EXAMPLE_ROOM.safety_factor(&positions), 12);
+    }
+
+    #[test]
+    fn position_after_example() {
+        let robot = parse("p=2,4 v=2,-3")[0];
+        let positions: Vec<_> = (0..=5)
+            .map(|seconds| EXAMPLE_ROOM.position_after(&robot, seconds))
+            .collect();
+        assert_eq!(positions, [(2, 4), (4, 1), (6, 5), (8, 2), (10, 6), (1, 3)]);
+
+        // Far into the future, and back into the past.
+        let period = EXAMPLE_ROOM.width * EXAMPLE_ROOM.height;
+        assert_eq!(EXAMPLE_ROOM.position_after(&robot, 5 + 1_000_000 * period), (1, 3));
+        assert_eq!(EXAMPLE_ROOM.position_after(&robot, -1), (0, 0));
+    }
+
+    #[test]
+    fn simulate_example() {
+        let mut positions = EXAMPLE_ROOM.simulate(&parse(EXAMPLE), PART1_SECONDS);
+        positions.sort_unstable();
+        assert_eq!(
+            positions,
+            [
+                (0, 2),
+                (1, 3),
+                (1, 6),
+                (2, 3),
+                (3, 5),
+                (4, 5),
+                (4, 5),
+                (5, 4),
+                (6, 0),
+                (6, 0),
+                (6, 6),
+                (9, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn find_tree() {
+        let (robots, tree_at) = hidden_tree(500);
+        assert_eq!(REAL_ROOM.find_tree(&robots), Some(tree_at));
+        assert_eq!(part2(&robots), tree_at);
+
+        for heuristic in [Heuristic::Variance, Heuristic::LargestComponent, Heuristic::NoOverlaps] {
+            let candidates = REAL_ROOM.candidates(&robots, heuristic, 3);
+            assert_eq!(candidates.len(), 3);
+            assert_eq!(candidates[0].seconds, tree_at, "{heuristic:?}");
+            assert!(candidates[0].score > candidates[1].score, "{heuristic:?}");
+        }
     }
 
     #[test]
-    fn part2_example() {
-        assert_eq!(part2(&parse("<EXAMPLE>")), "<RESULT>");
+    fn render_example() {
+        let robots = parse(EXAMPLE);
+        assert_eq!(
+            EXAMPLE_ROOM.render(&EXAMPLE_ROOM.simulate(&robots, 0)),
+            "\
+1.12.......
+...........
+...........
+......11.11
+1.1........
+.........1.
+.......1...
+"
+        );
+        assert_eq!(
+            EXAMPLE_ROOM.render(&EXAMPLE_ROOM.simulate(&robots, PART1_SECONDS)),
+            "\
+......2..1.
+...........
+1..........
+.11........
+.....1.....
+...12......
+.1....1....
+"
+        );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn frame_to_bytes_example() {
+        let positions = EXAMPLE_ROOM.simulate(&parse(EXAMPLE), PART1_SECONDS);
+
+        let pgm = String::from_utf8(frame_to_bytes(&EXAMPLE_ROOM, &positions, ImageFormat::Pgm));
+        let pgm = pgm.unwrap();
+        let lines: Vec<_> = pgm.lines().collect();
+        assert_eq!(lines[..3], ["P2", "11 7", "2"]);
+        assert_eq!(lines[3], "0 0 0 0 0 0 2 0 0 1 0");
+        assert_eq!(lines.len(), 3 + 7);
+
+        let pbm = String::from_utf8(frame_to_bytes(&EXAMPLE_ROOM, &positions, ImageFormat::Pbm));
+        let pbm = pbm.unwrap();
+        let lines: Vec<_> = pbm.lines().collect();
+        assert_eq!(lines[..2], ["P1", "11 7"]);
+        assert_eq!(lines[2], "0 0 0 0 0 0 1 0 0 1 0");
+        assert_eq!(lines.len(), 2 + 7);
+    }
+
+    #[test]
+    fn frame_to_bytes_wraps_lines() {
+        // Pile up enough robots in one spot for the grays to take up three digits.
+        let mut positions = vec![(50, 50); 100];
+        positions.extend((0..REAL_ROOM.width).map(|x| (x, x)));
+
+        for (format, header) in [(ImageFormat::Pbm, 2), (ImageFormat::Pgm, 3)] {
+            let image = String::from_utf8(frame_to_bytes(&REAL_ROOM, &positions, format));
+            let image = image.unwrap();
+            assert!(image.lines().all(|line| line.len() <= 70), "{format:?}");
+
+            let pixels: Vec<u16> = image
+                .lines()
+                .skip(header)
+                .flat_map(str::split_whitespace)
+                .map(|pixel| pixel.parse().unwrap())
+                .collect();
+            let expected: Vec<u16> = REAL_ROOM
+                .robots_on(&positions)
+                .iter()
+                .map(|robots| match format {
+                    ImageFormat::Pbm => u16::from(*robots > 0),
+                    ImageFormat::Pgm => *robots,
+                })
+                .collect();
+            // Wrapping doesn't lose or add any pixels.
+            assert_eq!(pixels, expected, "{format:?}");
+        }
+    }
+
+    #[test]
+    fn write_frames_example() {
+        let robots = parse(EXAMPLE);
+        let frames_dir = TempDir::new("day14-frames");
+        let dir = frames_dir.path();
+
+        let frames = write_frames_to(&EXAMPLE_ROOM, &robots, 0..3, ImageFormat::Pgm, dir).unwrap();
+        assert_eq!(frames, 3);
+        let first = std::fs::read_to_string(format!("{dir}/frame00000.pgm")).unwrap();
+        assert_eq!(first.lines().nth(3), Some("1 0 1 2 0 0 0 0 0 0 0"));
+        assert!(std::fs::exists(format!("{dir}/frame00002.pgm")).unwrap());
+
+        let top_dir = TempDir::new("day14-top-frames");
+        let dir = top_dir.path();
+        let (robots, tree_at) = hidden_tree(500);
+        let candidates =
+            write_top_frames_to(&REAL_ROOM, &robots, Heuristic::Variance, 2, ImageFormat::Pbm, dir);
+        let candidates = candidates.unwrap();
+        assert_eq!(candidates[0].seconds, tree_at);
+        for candidate in &candidates {
+            let path = format!("{dir}/frame{:05}.pbm", candidate.seconds);
+            assert!(std::fs::exists(path).unwrap());
+        }
+    }
+
+    #[test]
+    fn crt_and_period() {
+        assert_eq!(crt((2, 3), (3, 5)), Some(8));
+        assert_eq!(crt((0, 101), (0, 103)), Some(0));
+        assert_eq!(crt((1, 4), (3, 6)), None);
+        assert_eq!(REAL_ROOM.period(), 10403);
+        assert_eq!(Room { width: 4, height: 6 }.period(), 12);
+    }
+
+    /// Returns `count` robots that draw a Christmas tree in the real room at some point, along
+    /// with the second they do. The rest of the time, they're all over the place.
+    fn hidden_tree(count: usize) -> (Vec<Robot>, i64) {
+        const TREE_AT: i64 = 6_512;
+
+        // A triangle with a trunk, near the middle of the room.
+        let mut picture: Vec<Coord> = (0..20)
+            .flat_map(|row| (-row..=row).map(move |column| (50 + column, 30 + row)))
+            .chain((0..5).flat_map(|row| (-2..=2).map(move |column| (50 + column, 50 + row))))
+            .collect();
+        let mut lcg = Lcg::new(0x2024_0014);
+        let mut random = |bound: i64| lcg.below(bound as u64) as i64;
+
+        // Some robots aren't part of the picture, but they don't land on top of it either.
+        while picture.len() < count {
+            let position = (random(REAL_ROOM.width), random(REAL_ROOM.height));
+            if !picture.contains(&position) {
+                picture.push(position);
+            }
+        }
+
+        let robots = picture
+            .into_iter()
+            .map(|(x, y)| {
+                let velocity = (random(201) - 100, random(201) - 100);
+                let start = Robot {
+                    position: (x, y),
+                    velocity: (-velocity.0, -velocity.1),
+                };
+                Robot {
+                    position: REAL_ROOM.position_after(&start, TREE_AT),
+                    velocity,
+                }
+            })
+            .collect();
+        (robots, TREE_AT)
+    }
+
+    #[test]
+    fn safety_factor_middle() {
+        // Robots in the middle row or column don't count, so one quadrant is empty.
+        assert_eq!(EXAMPLE_ROOM.safety_factor(&[(0, 0), (10, 0), (5, 6), (0, 3)]), 0);
+        assert_eq!(EXAMPLE_ROOM.safety_factor(&[(0, 0), (10, 0), (0, 6), (10, 6), (10, 6)]), 2);
+
+        // With even sides, every robot is in a quadrant, and the halves are the same size.
+        let room = Room { width: 2, height: 2 };
+        assert_eq!(room.safety_factor(&[(0, 0), (1, 0), (0, 1), (1, 1)]), 1);
+        let room = Room { width: 10, height: 4 };
+        // One robot top left, two top right, three bottom left, and one bottom right.
+        let positions = [(4, 1), (5, 1), (5, 1), (4, 2), (4, 2), (4, 2), (9, 3)];
+        assert_eq!(room.safety_factor(&positions), 6);
+    }
+}
@@ -1,11 +1,11 @@
-use std::io::Write;
+use std::{collections::HashSet, io::Write};
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
 #[aoc_generator(day6)]
 fn parse(input: &str) -> Field {
     let mut field = Vec::new();
-    let mut start: Option<StartingSpot> = None;
+    let mut guards: Vec<StartingSpot> = Vec::new();
 
     for (y, line) in input.lines().enumerate() {
         let mut row = Vec::new();
@@ -13,14 +13,10 @@ fn parse(input: &str) -> Field {
         for (x, c) in line.chars().enumerate() {
             let space = Space::try_from(c).expect("That space character wasn't expected.");
 
-            // Don't set `start` if the starting position was found. There should only be one.
-            if start.is_none() {
-                start = match space {
-                    Space::Guard(dir) => Some(((x, y), dir)),
-                    _ => None,
-                };
-            } else if start.is_some() && space.is_guard() {
-                panic!("More than one starting space for the guard was found.");
+            // Guards are kept in reading order, so the first one found is "the" guard for
+            // everything that only cares about a single guard.
+            if let Space::Guard(dir) = space {
+                guards.push(((x, y), dir));
             }
 
             row.push(space);
@@ -29,9 +25,10 @@ fn parse(input: &str) -> Field {
         field.push(row);
     }
 
-    let start = start.expect("The starting point was not found.");
+    assert!(!guards.is_empty(), "The starting point was not found.");
+    let jumps = JumpTable::new(&field);
 
-    Field { field, start }
+    Field { field, guards, jumps }
 }
 
 #[aoc(day6, part1)]
@@ -41,44 +38,28 @@ fn part1(field: &Field) -> usize {
     // * Otherwise, take a step forward.
 
     // TODO: This is a quirk of using cargo-aoc: The input cannot be mutated because it is not an owned type.
-    let mut field = field.clone();
-
-    let (coord, mut dir) = field.starting_coord();
-    // SAFETY: `coord` is guaranteed to be valid by the input generator.
-    let mut spot = field.spot_at_mut(coord).unwrap();
-    // Mark the initial spot as visited.
-    spot.mark();
-
-    let mut visited_spots = 1usize;
-
-    loop {
-        // If this lands outside the grid, break out of the loop.
-        let Some(mut obstacle) = SpotMut::direction_fn(dir)(spot) else {
-            break;
-        };
-
-        if obstacle.is_occupied() {
-            // Do a 180 and turn around.
-            // SAFETY: We would have broken out of the loop if `obstacle`` was outside the grid (see above body).
-            spot = SpotMut::direction_fn(dir.rotate_right().rotate_right())(obstacle).unwrap();
-            // Turn right on the next iteration.
-            dir = dir.rotate_right();
-            continue;
-        }
-
-        if !obstacle.is_marked() {
-            obstacle.mark();
-            visited_spots += 1;
-        }
-
-        spot = obstacle;
-    }
+    let mut trail = field.clone();
+
+    // The puzzle promises that the guard eventually leaves, so this walk ends.
+    field
+        .walk()
+        // Only count a spot the first time the guard steps on it.
+        .filter(|(coord, dir, _)| trail.mark(*coord, *dir).is_some_and(|marked| marked))
+        .count()
+}
 
-    visited_spots
+/// Same as [part1], but counts the spots covered by every guard in the field.
+#[aoc(day6, part1, team)]
+fn part1_team(field: &Field) -> usize {
+    field.patrol_team().union().len()
 }
 
 #[aoc(day6, part2)]
-fn part2(_input: &Field) -> usize { 0 }
+fn part2(field: &Field) -> usize {
+    // The guard only ever touches the spots along its original route, so an obstruction
+    // placed anywhere else can't change where it walks.
+    field.loop_obstructions().count()
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Direction {
@@ -89,6 +70,16 @@ enum Direction {
 }
 
 impl Direction {
+    /// Returns the bit representing this direction in a [Trail].
+    fn bit(self) -> u8 {
+        match self {
+            Direction::North => 0b0001,
+            Direction::East => 0b0010,
+            Direction::South => 0b0100,
+            Direction::West => 0b1000,
+        }
+    }
+
     /// Rotate the direction 90 degrees.
     fn rotate_right(self) -> Self {
         match self {
@@ -98,6 +89,18 @@ impl Direction {
             Direction::West => Direction::North,
         }
     }
+
+    /// Returns the coordinates one step in this direction from `(x, y)`.
+    ///
+    /// Returns `None` if the step would go past the top or left edge of the grid.
+    fn step(self, (x, y): Coord) -> Option<Coord> {
+        match self {
+            Direction::North => Some((x, y.checked_sub(1)?)),
+            Direction::East => Some((x + 1, y)),
+            Direction::South => Some((x, y + 1)),
+            Direction::West => Some((x.checked_sub(1)?, y)),
+        }
+    }
 }
 
 /// The state of a space in the [Field].
@@ -109,8 +112,25 @@ enum Space {
     Obstruction,
     /// The starting position for a guard in a [Field] '^'.
     Guard(Direction),
-    /// The space has been visited before (an 'X').
-    Marked,
+    /// The space has been visited before, along with the directions the guard passed through it.
+    /// Drawn as `|`, `-` or `+` (or `X` if the directions aren't known).
+    Marked(Trail),
+}
+
+/// The set of directions a guard was facing while standing on a spot, stored as a bitmask.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+struct Trail(u8);
+
+impl Trail {
+    const VERTICAL: Self = Self(0b0101);
+    const HORIZONTAL: Self = Self(0b1010);
+
+    /// Returns this trail with `dir` added to it.
+    fn with(self, dir: Direction) -> Self { Self(self.0 | dir.bit()) }
+
+    fn is_vertical(self) -> bool { self.0 & Self::VERTICAL.0 != 0 }
+
+    fn is_horizontal(self) -> bool { self.0 & Self::HORIZONTAL.0 != 0 }
 }
 
 impl TryFrom<char> for Space {
@@ -124,7 +144,10 @@ impl TryFrom<char> for Space {
             '>' => Ok(Space::Guard(Direction::East)),
             '<' => Ok(Space::Guard(Direction::West)),
             'v' => Ok(Space::Guard(Direction::South)),
-            'X' => Ok(Space::Marked),
+            '|' => Ok(Space::Marked(Trail::VERTICAL)),
+            '-' => Ok(Space::Marked(Trail::HORIZONTAL)),
+            '+' => Ok(Space::Marked(Trail::VERTICAL.with(Direction::East).with(Direction::West))),
+            'X' => Ok(Space::Marked(Trail::default())),
             _ => Err(()),
         }
     }
@@ -139,16 +162,12 @@ impl From<Space> for char {
             Space::Guard(Direction::East) => '>',
             Space::Guard(Direction::West) => '<',
             Space::Guard(Direction::South) => 'v',
-            Space::Marked => 'X',
-        }
-    }
-}
-
-impl Space {
-    fn is_guard(&self) -> bool {
-        match self {
-            Space::Guard(_) => true,
-            _ => false,
+            Space::Marked(trail) => match (trail.is_vertical(), trail.is_horizontal()) {
+                (true, true) => '+',
+                (true, false) => '|',
+                (false, true) => '-',
+                (false, false) => 'X',
+            },
         }
     }
 }
@@ -158,71 +177,253 @@ type Coord = (usize, usize);
 
 type StartingSpot = (Coord, Direction);
 
+/// Every state the guard was in while walking around a [Field].
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Patrol {
+    states: HashSet<(Coord, Direction)>,
+    /// `true` if the guard ended up walking in circles instead of leaving the [Field].
+    looped: bool,
+}
+
+impl Patrol {
+    /// Returns the coordinates the guard walked over, regardless of which way it was facing.
+    fn visited(&self) -> HashSet<Coord> {
+        self.states.iter().map(|(coord, _)| *coord).collect()
+    }
+}
+
+/// The spots covered by several guards patrolling a [Field] together (see [Field::patrol_team]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TeamPatrol {
+    /// The spots each guard walked over, in the same order as the guards in the [Field].
+    visited: Vec<HashSet<Coord>>,
+    /// `true` if the team ended up walking in circles instead of every guard leaving the [Field].
+    looped: bool,
+}
+
+impl TeamPatrol {
+    /// Returns the spots walked over by at least one guard.
+    fn union(&self) -> HashSet<Coord> {
+        self.visited.iter().flatten().copied().collect()
+    }
+
+    /// Returns the spots walked over by every guard.
+    #[allow(dead_code)]
+    fn intersection(&self) -> HashSet<Coord> {
+        let Some((first, rest)) = self.visited.split_first() else {
+            return HashSet::new();
+        };
+
+        first
+            .iter()
+            .filter(|coord| rest.iter().all(|visited| visited.contains(coord)))
+            .copied()
+            .collect()
+    }
+}
+
 /// Imperitive representation of the puzzle input.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct Field {
     field: Vec<Vec<Space>>,
-    start: StartingSpot,
+    /// Every guard in the field, in reading order (top to bottom, left to right).
+    guards: Vec<StartingSpot>,
+    /// Kept in sync with the obstructions in `field` by [Field::replace_space].
+    jumps: JumpTable,
 }
 
 impl Field {
     /// Returns the starting coordinates and direction of the guard in the current `Field`.
-    fn starting_coord(&self) -> StartingSpot { self.start }
+    ///
+    /// If there's more than one guard, this is the first one in reading order. The others
+    /// are only taken into account by [Field::patrol_team].
+    fn starting_coord(&self) -> StartingSpot { self.guards[0] }
 
-    /// Returns a pinned location for easy traversal in the current `Field`.
+    /// Replaces the space at `(x, y)` with `space`, returning the space that was there before.
     ///
     /// Returns `None` if `(x, y)` are invalid coordinates.
-    fn spot_at(&self, (x, y): Coord) -> Option<Spot> {
-        let row = self.field.get(y)?;
-        let _ = row.get(x)?;
-        Some(Spot {
-            field: self,
-            coord: (x, y),
-        })
+    fn replace_space(&mut self, (x, y): Coord, space: Space) -> Option<Space> {
+        let spot = self.field.get_mut(y)?.get_mut(x)?;
+        let prev = std::mem::replace(spot, space);
+
+        match (prev, space) {
+            (Space::Obstruction, Space::Obstruction) => {}
+            (_, Space::Obstruction) => self.jumps.insert((x, y)),
+            (Space::Obstruction, _) => self.jumps.remove((x, y)),
+            _ => {}
+        }
+
+        Some(prev)
     }
 
-    /// Returns `Some(true)` if the spot at `(x, y)` is [Space::Obstruction].
+    /// Walks the guard from its starting spot until it either leaves the `Field` or
+    /// returns to a spot it already stood on while facing the same direction (a loop).
+    fn patrol(&self) -> Patrol {
+        let mut states = HashSet::new();
+
+        for (coord, dir, event) in self.walk() {
+            // Standing in the same spot facing the same way means the guard will
+            // repeat every step it took since then, forever.
+            // (Leaving the field doesn't put the guard in a new state, so it's skipped.)
+            if event != WalkEvent::Exit && !states.insert((coord, dir)) {
+                return Patrol { states, looped: true };
+            }
+        }
+
+        Patrol { states, looped: false }
+    }
+
+    /// Returns an iterator over every step the guard takes, starting from its starting spot.
+    fn walk(&self) -> GuardWalk<'_> {
+        let (coord, dir) = self.starting_coord();
+        GuardWalk {
+            // SAFETY: `coord` is guaranteed to be valid by the input generator.
+            spot: Some(self.spot_at(coord).unwrap()),
+            dir,
+            started: false,
+        }
+    }
+
+    /// Walks every guard in the `Field` at the same time, one step per guard per tick.
     ///
-    /// Returns `None` if `(x, y)` are invalid coordinates.
-    fn is_occupied_at(&self, (x, y): Coord) -> Option<bool> {
-        self.field
-            .get(y)
-            .map(|row| row.get(x).is_some_and(|spot| *spot == Space::Obstruction))
+    /// Each tick, the guards move in reading order of where they started, and:
+    /// * A guard turns right in place if the spot in front of it is an obstruction, or if
+    ///   another guard stood on it at the start of the tick (even one that's about to move away).
+    /// * A guard also turns right if a guard before it already stepped onto that spot this tick.
+    /// * A guard that steps off the edge is gone for good, and stops getting in anyone's way.
+    ///
+    /// The walk ends once every guard has left, or once all guards are in a state they were
+    /// in together before (a loop).
+    fn patrol_team(&self) -> TeamPatrol {
+        let mut guards: Vec<Option<StartingSpot>> = self.guards.iter().copied().map(Some).collect();
+        let mut visited: Vec<HashSet<Coord>> = self
+            .guards
+            .iter()
+            .map(|(coord, _)| HashSet::from([*coord]))
+            .collect();
+        let mut states = HashSet::new();
+
+        while guards.iter().any(Option::is_some) {
+            if !states.insert(guards.clone()) {
+                return TeamPatrol { visited, looped: true };
+            }
+
+            let standing: HashSet<Coord> = guards.iter().flatten().map(|(coord, _)| *coord).collect();
+            let mut claimed = HashSet::new();
+
+            for (guard, visited) in guards.iter_mut().zip(visited.iter_mut()) {
+                let Some((coord, dir)) = *guard else {
+                    continue;
+                };
+
+                let Some(next) = dir.step(coord).filter(|next| self.spot_at(*next).is_some()) else {
+                    // This guard walked off the edge of the field.
+                    *guard = None;
+                    continue;
+                };
+
+                let blocked = self.is_occupied_at(next).is_some_and(|occupied| occupied)
+                    || standing.contains(&next)
+                    || !claimed.insert(next);
+
+                *guard = if blocked {
+                    Some((coord, dir.rotate_right()))
+                } else {
+                    visited.insert(next);
+                    Some((next, dir))
+                };
+            }
+        }
+
+        TeamPatrol { visited, looped: false }
     }
 
-    /// Returns `Some(true)` if the spot at `(x, y)` is [Space::Marked].
+    /// Returns `true` if the guard never leaves the `Field`.
     ///
-    /// Returns `None` if `(x, y)` are invalid coordinates.
-    fn is_marked_at(&self, (x, y): Coord) -> Option<bool> {
-        self.field
-            .get(y)
-            .map(|row| row.get(x).is_some_and(|spot| *spot == Space::Marked))
+    /// Gives the same answer as [Field::patrol], but jumps from obstruction to obstruction
+    /// using the [JumpTable] instead of walking every spot in between.
+    fn guard_loops(&self) -> bool {
+        let (mut coord, mut dir) = self.starting_coord();
+        // The guard only changes direction right before an obstruction, so remembering
+        // where it turned is enough to notice it walking in circles.
+        let mut turns = HashSet::new();
+
+        while let Some(stop) = self.jumps.jump(coord, dir) {
+            coord = stop;
+            dir = dir.rotate_right();
+
+            if !turns.insert((coord, dir)) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Returns every spot where placing a single obstruction traps the guard in a loop,
+    /// in reading order (top to bottom, left to right).
+    ///
+    /// The guard's starting spot is never a candidate, since the guard would see it being placed.
+    fn loop_obstructions(&self) -> impl Iterator<Item = Coord> + '_ {
+        let (start, _) = self.starting_coord();
+
+        let mut candidates: Vec<Coord> = self
+            .patrol()
+            .visited()
+            .into_iter()
+            .filter(|coord| *coord != start)
+            .collect();
+        candidates.sort_unstable_by_key(|&(x, y)| (y, x));
+
+        // Every candidate is tried against the same scratch copy of the field, and the
+        // obstruction is removed again before trying the next one.
+        let mut scratch = self.clone();
+        candidates.into_iter().filter(move |coord| {
+            // SAFETY: `coord` was visited by the guard, so it's inside the field.
+            let prev = scratch.replace_space(*coord, Space::Obstruction).unwrap();
+            let loops = scratch.guard_loops();
+            scratch.replace_space(*coord, prev);
+            loops
+        })
     }
 
     /// Returns a pinned location for easy traversal in the current `Field`.
     ///
     /// Returns `None` if `(x, y)` are invalid coordinates.
-    fn spot_at_mut(&mut self, (x, y): Coord) -> Option<SpotMut> {
+    fn spot_at(&self, (x, y): Coord) -> Option<Spot<'_>> {
         let row = self.field.get(y)?;
         let _ = row.get(x)?;
-        Some(SpotMut {
+        Some(Spot {
             field: self,
             coord: (x, y),
         })
     }
 
-    /// Marks the spot at `(x, y)` as visited. The value returned by `Some` indicates whether the spot was marked successfully:
+    /// Returns `Some(true)` if the spot at `(x, y)` is [Space::Obstruction].
+    ///
+    /// Returns `None` if `(x, y)` are invalid coordinates.
+    fn is_occupied_at(&self, (x, y): Coord) -> Option<bool> {
+        self.field
+            .get(y)
+            .map(|row| row.get(x).is_some_and(|spot| *spot == Space::Obstruction))
+    }
+
+    /// Marks the spot at `(x, y)` as visited by a guard facing `dir`. The value returned by `Some` indicates whether the spot was marked for the first time:
     /// * `Some(true)` is returned if the spot was not already marked and not an obstruction.
-    /// * `Some(false)` is returned otherwise.
+    /// * `Some(false)` is returned otherwise. `dir` is still added to an already marked spot.
     ///
     /// Returns `None` if `(x, y)` is outside the grid.
-    fn mark(&mut self, (x, y): Coord) -> Option<bool> {
+    fn mark(&mut self, (x, y): Coord, dir: Direction) -> Option<bool> {
         let row = self.field.get_mut(y)?;
         let spot = row.get_mut(x)?;
         match *spot {
-            Space::Marked | Space::Obstruction => Some(false),
+            Space::Obstruction => Some(false),
+            Space::Marked(trail) => {
+                *spot = Space::Marked(trail.with(dir));
+                Some(false)
+            }
             _ => {
-                *spot = Space::Marked;
+                *spot = Space::Marked(Trail::default().with(dir));
                 Some(true)
             }
         }
@@ -246,6 +447,88 @@ impl Field {
     }
 }
 
+/// The obstructions of a [Field], sorted by position along each row and column.
+///
+/// This lets the guard skip straight to the next obstruction in front of it, instead
+/// of checking every spot along the way.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct JumpTable {
+    /// `rows[y]` holds the `x` coordinate of every obstruction in row `y`, in ascending order.
+    rows: Vec<Vec<usize>>,
+    /// `columns[x]` holds the `y` coordinate of every obstruction in column `x`, in ascending order.
+    columns: Vec<Vec<usize>>,
+}
+
+impl JumpTable {
+    fn new(field: &[Vec<Space>]) -> Self {
+        let width = field.iter().map(Vec::len).max().unwrap_or(0);
+        let mut rows = vec![Vec::new(); field.len()];
+        let mut columns = vec![Vec::new(); width];
+
+        // Scanning in reading order keeps both lists sorted without any extra work.
+        for (y, row) in field.iter().enumerate() {
+            for (x, space) in row.iter().enumerate() {
+                if *space == Space::Obstruction {
+                    rows[y].push(x);
+                    columns[x].push(y);
+                }
+            }
+        }
+
+        Self { rows, columns }
+    }
+
+    /// Returns the spot the guard stops at when walking from `(x, y)` towards `dir`: the spot
+    /// directly in front of the next obstruction. This is `(x, y)` itself if the obstruction
+    /// is right in front of the guard.
+    ///
+    /// Returns `None` if there are no obstructions ahead, meaning the guard walks off the field.
+    fn jump(&self, (x, y): Coord, dir: Direction) -> Option<Coord> {
+        match dir {
+            Direction::North => {
+                let column = self.columns.get(x)?;
+                let ahead = column.partition_point(|&oy| oy < y);
+                Some((x, column.get(ahead.checked_sub(1)?)? + 1))
+            }
+            Direction::South => {
+                let column = self.columns.get(x)?;
+                let ahead = column.partition_point(|&oy| oy <= y);
+                Some((x, column.get(ahead)? - 1))
+            }
+            Direction::West => {
+                let row = self.rows.get(y)?;
+                let ahead = row.partition_point(|&ox| ox < x);
+                Some((row.get(ahead.checked_sub(1)?)? + 1, y))
+            }
+            Direction::East => {
+                let row = self.rows.get(y)?;
+                let ahead = row.partition_point(|&ox| ox <= x);
+                Some((row.get(ahead)? - 1, y))
+            }
+        }
+    }
+
+    /// Adds an obstruction at `(x, y)`, if there isn't one already.
+    fn insert(&mut self, (x, y): Coord) {
+        if let Err(idx) = self.rows[y].binary_search(&x) {
+            self.rows[y].insert(idx, x);
+        }
+        if let Err(idx) = self.columns[x].binary_search(&y) {
+            self.columns[x].insert(idx, y);
+        }
+    }
+
+    /// Removes the obstruction at `(x, y)`, if there is one.
+    fn remove(&mut self, (x, y): Coord) {
+        if let Ok(idx) = self.rows[y].binary_search(&x) {
+            self.rows[y].remove(idx);
+        }
+        if let Ok(idx) = self.columns[x].binary_search(&y) {
+            self.columns[x].remove(idx);
+        }
+    }
+}
+
 /// A pinned location for easy traversal inside a [Field].
 struct Spot<'f> {
     field: &'f Field,
@@ -281,22 +564,22 @@ impl<'f> Spot<'f> {
     }
 
     fn north(self) -> Option<Self> {
-        let coord = (self.coord.0, self.coord.1 - 1);
+        let coord = Direction::North.step(self.coord)?;
         self.field.spot_at(coord)
     }
 
     fn east(self) -> Option<Self> {
-        let coord = (self.coord.0 + 1, self.coord.1);
+        let coord = Direction::East.step(self.coord)?;
         self.field.spot_at(coord)
     }
 
     fn south(self) -> Option<Self> {
-        let coord = (self.coord.0, self.coord.1 + 1);
+        let coord = Direction::South.step(self.coord)?;
         self.field.spot_at(coord)
     }
 
     fn west(self) -> Option<Self> {
-        let coord = (self.coord.0 - 1, self.coord.1);
+        let coord = Direction::West.step(self.coord)?;
         self.field.spot_at(coord)
     }
 
@@ -306,85 +589,312 @@ impl<'f> Spot<'f> {
     }
 }
 
-/// A cursor for easy traversal inside a [Field]. It leaves a trail around the `Field` as it traverses.
-struct SpotMut<'f> {
-    field: &'f mut Field,
-    coord: Coord,
+/// What the guard did to end up in a state yielded by [GuardWalk].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum WalkEvent {
+    /// The guard hasn't moved from its starting spot yet.
+    Start,
+    /// The guard took a step forward.
+    Step,
+    /// The guard turned right in place, since something was directly in front of it.
+    Turn,
+    /// The guard stepped off the edge of the [Field] from this spot. Always the last event.
+    Exit,
 }
 
-type DirectionFnMut<'f> = fn(SpotMut<'f>) -> Option<SpotMut<'f>>;
+/// An iterator over the guard's walk around a [Field], yielding where the guard is,
+/// which way it's facing and how it got there.
+///
+/// The walk never ends if the guard is stuck in a loop (see [Field::patrol]).
+struct GuardWalk<'f> {
+    /// The guard's current spot. `None` once the guard has left the [Field].
+    spot: Option<Spot<'f>>,
+    dir: Direction,
+    started: bool,
+}
 
-impl<'f> std::fmt::Debug for SpotMut<'f> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.coord.0, self.coord.1)
+impl<'f> Iterator for GuardWalk<'f> {
+    type Item = (Coord, Direction, WalkEvent);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let spot = self.spot.clone()?;
+
+        if !self.started {
+            self.started = true;
+            return Some((spot.coord, self.dir, WalkEvent::Start));
+        }
+
+        match Spot::direction_fn(self.dir)(spot.clone()) {
+            None => {
+                self.spot = None;
+                Some((spot.coord, self.dir, WalkEvent::Exit))
+            }
+            Some(ahead) if ahead.is_occupied() => {
+                // Turn in place. The guard doesn't move until the way ahead is clear.
+                self.dir = self.dir.rotate_right();
+                Some((spot.coord, self.dir, WalkEvent::Turn))
+            }
+            Some(ahead) => {
+                let coord = ahead.coord;
+                self.spot = Some(ahead);
+                Some((coord, self.dir, WalkEvent::Step))
+            }
+        }
     }
 }
 
-impl<'f> SpotMut<'f> {
-    fn direction_fn(dir: Direction) -> DirectionFnMut<'f> {
-        match dir {
-            Direction::North => Self::north,
-            Direction::East => Self::east,
-            Direction::South => Self::south,
-            Direction::West => Self::west,
+/// Draws the `field` with all marks, one row per line.
+fn field_to_bytes(field: &Field) -> Vec<u8> {
+    let byteiter = field
+        .field
+        .iter()
+        .map(|row| row.iter().map(|space| char::from(*space) as u8));
+
+    // The `+1` here is for the newlines at the end of each row.
+    let bufsize = field.field.iter().map(|row| row.len() + 1).sum();
+    let mut buf: Vec<u8> = Vec::with_capacity(bufsize);
+
+    for row in byteiter {
+        for space in row {
+            buf.push(space);
         }
+        buf.push(b'\n');
     }
 
-    fn north(self) -> Option<Self> {
-        let coord = (self.coord.0, self.coord.1 - 1);
-        self.field.spot_at_mut(coord)
+    buf
+}
+
+/// Writes the `field` to a file with all marks. Overwrites the file at `path` if it exists.
+#[allow(dead_code)]
+fn write_field_to(field: &Field, path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    file.write_all(&field_to_bytes(field))
+}
+
+/// Writes every step of the guard's walk as a separate frame into the directory `dir`
+/// (`frame00000.txt`, `frame00001.txt`, ...), using [write_field_to]. Each frame shows the
+/// trail left so far, with the guard drawn where it's standing.
+///
+/// The walk stops once the guard leaves the `field` or starts walking in circles.
+/// Returns the number of frames written.
+#[allow(dead_code)]
+fn write_walk_frames_to(field: &Field, dir: &str) -> std::io::Result<usize> {
+    std::fs::create_dir_all(dir)?;
+
+    let mut trail = field.clone();
+    let mut states = HashSet::new();
+    let mut frames = 0usize;
+
+    for (coord, facing, event) in field.walk() {
+        if event == WalkEvent::Exit {
+            break;
+        }
+        trail.mark(coord, facing);
+
+        // Draw the guard on top of its trail just for this frame.
+        // SAFETY: The guard never stands outside the field.
+        let under = trail.replace_space(coord, Space::Guard(facing)).unwrap();
+        write_field_to(&trail, &format!("{dir}/frame{frames:05}.txt"))?;
+        trail.replace_space(coord, under);
+        frames += 1;
+
+        // The frame where the loop closes is written, but nothing after it.
+        if !states.insert((coord, facing)) {
+            break;
+        }
     }
 
-    fn east(self) -> Option<Self> {
-        let coord = (self.coord.0 + 1, self.coord.1);
-        self.field.spot_at_mut(coord)
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    const EXAMPLE: &str = "\
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE)), 41);
     }
 
-    fn south(self) -> Option<Self> {
-        let coord = (self.coord.0, self.coord.1 + 1);
-        self.field.spot_at_mut(coord)
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE)), 6);
     }
 
-    fn west(self) -> Option<Self> {
-        let coord = (self.coord.0 - 1, self.coord.1);
-        self.field.spot_at_mut(coord)
+    #[test]
+    fn walk_turns_in_place() {
+        let field = parse(EXAMPLE);
+        let walk: Vec<_> = field.walk().take(7).collect();
+        assert_eq!(
+            walk,
+            [
+                ((4, 6), Direction::North, WalkEvent::Start),
+                ((4, 5), Direction::North, WalkEvent::Step),
+                ((4, 4), Direction::North, WalkEvent::Step),
+                ((4, 3), Direction::North, WalkEvent::Step),
+                ((4, 2), Direction::North, WalkEvent::Step),
+                ((4, 1), Direction::North, WalkEvent::Step),
+                ((4, 1), Direction::East, WalkEvent::Turn),
+            ]
+        );
     }
 
-    fn is_occupied(&self) -> bool {
-        // SAFETY: This spot is within the field, as guaranteed by [Field::spot_at_mut].
-        self.field.is_occupied_at(self.coord).unwrap()
+    #[test]
+    fn walk_exits_top_left_edge() {
+        let field = parse("..\n^.");
+        let walk: Vec<_> = field.walk().collect();
+        assert_eq!(
+            walk,
+            [
+                ((0, 1), Direction::North, WalkEvent::Start),
+                ((0, 0), Direction::North, WalkEvent::Step),
+                ((0, 0), Direction::North, WalkEvent::Exit),
+            ]
+        );
+
+        let field = parse("<.");
+        assert_eq!(field.walk().last(), Some(((0, 0), Direction::West, WalkEvent::Exit)));
     }
 
-    fn is_marked(&self) -> bool {
-        // SAFETY: This spot is within the field, as guaranteed by [Field::spot_at_mut].
-        self.field.is_marked_at(self.coord).unwrap()
+    #[test]
+    fn loop_obstructions_example() {
+        let field = parse(EXAMPLE);
+        assert!(!field.guard_loops());
+        assert!(!field.patrol().looped);
+        assert_eq!(
+            field.loop_obstructions().collect::<Vec<_>>(),
+            [(3, 6), (6, 7), (7, 7), (1, 8), (3, 8), (7, 9)]
+        );
     }
 
-    fn mark(&mut self) -> bool {
-        // SAFETY: This spot is within the field, as guaranteed by [Field::spot_at_mut].
-        self.field.mark(self.coord).unwrap()
+    #[test]
+    fn jumping_matches_walking() {
+        let mut field = parse(EXAMPLE);
+        let (start, _) = field.starting_coord();
+
+        for y in 0..10 {
+            for x in 0..10 {
+                if (x, y) == start {
+                    continue;
+                }
+
+                let prev = field.replace_space((x, y), Space::Obstruction).unwrap();
+                assert_eq!(field.guard_loops(), field.patrol().looped, "obstruction at ({x}, {y})");
+                field.replace_space((x, y), prev);
+            }
+        }
+
+        // Removing every added obstruction should leave the table as it was parsed.
+        assert_eq!(field.jumps, parse(EXAMPLE).jumps);
     }
-}
 
-/// Writes the `field` to a file with all marks. Overwrites the file at `path` if it exists.
-#[allow(dead_code)]
-fn write_field_to(field: &Field, path: &str) -> std::io::Result<()> {
-    let byteiter = field
-        .field
-        .iter()
-        .map(|row| row.iter().map(|space| char::from(*space) as u8));
+    #[test]
+    fn trail_glyphs_example() {
+        // The first of the six loops from the puzzle description. The puzzle keeps drawing
+        // the guard on its starting spot, but here it's a `+` since the guard turns there.
+        let mut field = parse(EXAMPLE);
+        field.replace_space((3, 6), Space::Obstruction);
 
-    // The `+1` here is for the newlines at the end of each row.
-    let bufsize = field.field.iter().map(|row| row.len() + 1).sum();
-    let mut buf: Vec<u8> = Vec::with_capacity(bufsize);
+        let patrol = field.patrol();
+        assert!(patrol.looped);
 
-    for row in byteiter {
-        for space in row {
-            buf.push(space);
+        let mut trail = field.clone();
+        for (coord, dir) in patrol.states {
+            trail.mark(coord, dir);
         }
-        buf.push(b'\n');
+
+        assert_eq!(
+            String::from_utf8(field_to_bytes(&trail)).unwrap(),
+            "\
+....#.....
+....+---+#
+....|...|.
+..#.|...|.
+....|..#|.
+....|...|.
+.#.#+---+.
+........#.
+#.........
+......#...
+"
+        );
     }
 
-    let mut file = std::fs::File::create(path)?;
-    file.write_all(&buf)
+    #[test]
+    fn walk_frames_example() {
+        let field = parse(EXAMPLE);
+        let dir = TempDir::new("day6-frames");
+        let dir = dir.path();
+
+        let frames = write_walk_frames_to(&field, dir).unwrap();
+        // Every event except leaving the field gets a frame.
+        assert_eq!(frames, field.walk().count() - 1);
+
+        let first = std::fs::read_to_string(format!("{dir}/frame00000.txt")).unwrap();
+        assert_eq!(first.lines().nth(6), Some(".#..^....."));
+
+        let second = std::fs::read_to_string(format!("{dir}/frame00001.txt")).unwrap();
+        assert_eq!(second.lines().nth(5), Some("....^....."));
+        assert_eq!(second.lines().nth(6), Some(".#..|....."));
+    }
+
+    #[test]
+    fn patrol_team_of_one_matches_patrol() {
+        let field = parse(EXAMPLE);
+        assert_eq!(part1_team(&field), part1(&field));
+
+        let team = field.patrol_team();
+
+        assert!(!team.looped);
+        assert_eq!(team.visited, [field.patrol().visited()]);
+        assert_eq!(team.union(), team.intersection());
+    }
+
+    #[test]
+    fn patrol_team_head_on() {
+        // Both guards want the middle spot on the second tick. The first guard gets it and
+        // the second turns right to face north. On the third tick, the first guard is blocked
+        // by the second guard (still standing there at the start of the tick) and turns to
+        // face south, while the second guard leaves through the top edge. The first guard
+        // leaves through the bottom edge on the fourth tick.
+        let field = parse(">...<");
+        assert_eq!(field.starting_coord(), ((0, 0), Direction::East));
+
+        let team = field.patrol_team();
+        assert!(!team.looped);
+        assert_eq!(
+            team.visited,
+            [HashSet::from([(0, 0), (1, 0), (2, 0)]), HashSet::from([(4, 0), (3, 0)])]
+        );
+        assert_eq!(team.union().len(), 5);
+        assert!(team.intersection().is_empty());
+    }
+
+    #[test]
+    fn patrol_team_example() {
+        // The example, with a second guard patrolling the bottom left corner.
+        let field = parse(&EXAMPLE.replace("#.........\n", "#...>.....\n"));
+        let team = field.patrol_team();
+
+        assert!(!team.looped);
+        assert_eq!(team.visited.len(), 2);
+        for visited in &team.visited {
+            assert!(team.intersection().is_subset(visited));
+            assert!(team.union().is_superset(visited));
+        }
+        assert!(team.union().len() > field.patrol().visited().len());
+    }
 }
@@ -1,31 +1,471 @@
+use std::{
+    fmt::{Debug, Display},
+    num::NonZeroUsize,
+    ops::ControlFlow,
+};
+
 use aoc_runner_derive::{aoc, aoc_generator};
+
 #[aoc_generator(day7)]
-fn parse(_input: &str) -> String {
-    todo!()
+fn parse(input: &str) -> Vec<Equation> {
+    input
+        .lines()
+        .map(|line| parse_equation(line).expect("That equation wasn't formatted as `target: n1 n2 ...`."))
+        .collect()
 }
 
 #[aoc(day7, part1)]
-fn part1(_input: &str) -> String {
-    todo!()
+fn part1(equations: &[Equation]) -> u64 {
+    calibration_result(equations, &PART1_OPERATORS)
 }
 
 #[aoc(day7, part2)]
-fn part2(_input: &str) -> String {
-    todo!()
+fn part2(equations: &[Equation]) -> u64 {
+    calibration_result(equations, &PART2_OPERATORS)
+}
+
+/// Sums the targets of every equation that can be solved using `operators`.
+///
+/// The equations are split evenly between one thread per available core.
+fn calibration_result(equations: &[Equation], operators: &[Operator]) -> u64 {
+    let threads = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunk_size = equations.len().div_ceil(threads).max(1);
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = equations
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .filter(|equation| equation.is_solvable(operators))
+                        .map(|equation| equation.target)
+                        .sum::<u64>()
+                })
+            })
+            .collect();
+
+        handles.into_iter().map(|handle| handle.join().unwrap()).sum()
+    })
+}
+
+/// The operators available in part one.
+const PART1_OPERATORS: [Operator; 2] = [Operator::Add, Operator::Multiply];
+
+/// The operators available in part two.
+const PART2_OPERATORS: [Operator; 3] = [Operator::Add, Operator::Multiply, Operator::Concatenate];
+
+/// A function that combines the running total (on the left) with the next operand (on the right).
+///
+/// Returns `None` if the result doesn't fit in a `u64`.
+type OperatorFn = dyn Fn(u64, u64) -> Option<u64> + Sync;
+
+/// An operator that can be placed between two operands of an [Equation].
+#[derive(Clone, Copy)]
+enum Operator<'a> {
+    /// `+`
+    Add,
+    /// `*`
+    Multiply,
+    /// `||`, which glues the digits of the right operand onto the end of the left one.
+    Concatenate,
+    /// A user supplied operator, drawn using the given symbol.
+    #[allow(dead_code)]
+    Custom(&'a str, &'a OperatorFn),
+}
+
+impl Operator<'_> {
+    /// Combines the running total `lhs` with the next operand `rhs`.
+    ///
+    /// Returns `None` if the result doesn't fit in a `u64`.
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+        match self {
+            Operator::Add => lhs.checked_add(rhs),
+            Operator::Multiply => lhs.checked_mul(rhs),
+            Operator::Concatenate => lhs.checked_mul(10u64.checked_pow(digits(rhs))?)?.checked_add(rhs),
+            Operator::Custom(_, apply) => apply(lhs, rhs),
+        }
+    }
+
+    /// Undoes this operator: returns the running total `lhs` that produces `total` when
+    /// combined with the operand `rhs`.
+    ///
+    /// Returns `None` if there's no such `lhs`, if `rhs` is zero for [Operator::Multiply]
+    /// (where any `lhs` would do), or if this is a custom operator.
+    fn unapply(&self, total: u64, rhs: u64) -> Option<u64> {
+        match self {
+            Operator::Add => total.checked_sub(rhs),
+            Operator::Multiply => (rhs != 0 && total.is_multiple_of(rhs)).then(|| total / rhs),
+            Operator::Concatenate => {
+                let shift = 10u64.checked_pow(digits(rhs))?;
+                (total % shift == rhs).then(|| total / shift)
+            }
+            Operator::Custom(..) => None,
+        }
+    }
+
+    /// Returns `true` if [Operator::unapply] knows how to undo this operator.
+    fn is_invertible(&self) -> bool { !matches!(self, Operator::Custom(..)) }
+
+    /// Returns `true` if applying this operator to positive operands can never make the
+    /// running total smaller. Custom operators are never assumed to be.
+    fn never_decreases(&self) -> bool { !matches!(self, Operator::Custom(..)) }
+
+    fn symbol(&self) -> &str {
+        match self {
+            Operator::Add => "+",
+            Operator::Multiply => "*",
+            Operator::Concatenate => "||",
+            Operator::Custom(symbol, _) => symbol,
+        }
+    }
+}
+
+impl Debug for Operator<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+/// Returns the number of decimal digits in `n`.
+fn digits(n: u64) -> u32 { n.checked_ilog10().unwrap_or(0) + 1 }
+
+/// A line of calibration input: a target value and the operands that should produce it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Equation {
+    target: u64,
+    operands: Vec<u64>,
+}
+
+impl Equation {
+    /// Returns `true` if some mix of `operators` placed between the operands, evaluated
+    /// left to right, produces the target.
+    fn is_solvable(&self, operators: &[Operator]) -> bool {
+        self.visit_solutions(operators, &mut |_| ControlFlow::Break(())).is_break()
+    }
+
+    /// Returns one mix of `operators` that solves this equation, if there is one.
+    #[allow(dead_code)]
+    fn solution<'o>(&self, operators: &[Operator<'o>]) -> Option<Solution<'_, 'o>> {
+        let mut solution = None;
+        let _ = self.visit_solutions(operators, &mut |chosen| {
+            solution = Some(chosen.to_vec());
+            ControlFlow::Break(())
+        });
+
+        solution.map(|operators| Solution { equation: self, operators })
+    }
+
+    /// Returns every mix of `operators` that solves this equation.
+    #[allow(dead_code)]
+    fn solutions<'o>(&self, operators: &[Operator<'o>]) -> Vec<Solution<'_, 'o>> {
+        let mut solutions = Vec::new();
+        let _ = self.visit_solutions(operators, &mut |chosen| {
+            solutions.push(Solution {
+                equation: self,
+                operators: chosen.to_vec(),
+            });
+            ControlFlow::Continue(())
+        });
+
+        solutions
+    }
+
+    /// Returns the number of distinct mixes of `operators` that solve this equation.
+    #[allow(dead_code)]
+    fn count_solutions(&self, operators: &[Operator]) -> usize {
+        let mut count = 0usize;
+        let _ = self.visit_solutions(operators, &mut |_| {
+            count += 1;
+            ControlFlow::Continue(())
+        });
+
+        count
+    }
+
+    /// Calls `visit` with the operators (in order, one per gap between operands) of each
+    /// way to solve this equation, until `visit` returns [ControlFlow::Break].
+    ///
+    /// Searches backwards from the target when every operator can be undone, and falls
+    /// back to trying every mix of operators otherwise.
+    fn visit_solutions<'o>(&self, operators: &[Operator<'o>], visit: &mut Visitor<'_, 'o>) -> ControlFlow<()> {
+        // Multiplying by zero can't be undone, since every running total gives the same result.
+        let positive = !self.operands.contains(&0);
+
+        if positive && operators.iter().all(Operator::is_invertible) {
+            self.visit_backwards(operators, visit)
+        } else {
+            self.visit_forwards(operators, visit)
+        }
+    }
+
+    /// Brute force: tries every mix of `operators` from the first operand onwards.
+    fn visit_forwards<'o>(&self, operators: &[Operator<'o>], visit: &mut Visitor<'_, 'o>) -> ControlFlow<()> {
+        let Some((first, rest)) = self.operands.split_first() else {
+            return ControlFlow::Continue(());
+        };
+
+        // Once the running total passes the target it can't come back down, unless some
+        // operator or operand is allowed to shrink it.
+        let prune = !self.operands.contains(&0) && operators.iter().all(Operator::never_decreases);
+        let mut chosen = Vec::with_capacity(rest.len());
+        self.reaches(*first, rest, operators, prune, &mut chosen, visit)
+    }
+
+    fn reaches<'o>(
+        &self,
+        total: u64,
+        rest: &[u64],
+        operators: &[Operator<'o>],
+        prune: bool,
+        chosen: &mut Vec<Operator<'o>>,
+        visit: &mut Visitor<'_, 'o>,
+    ) -> ControlFlow<()> {
+        let Some((next, rest)) = rest.split_first() else {
+            if total == self.target {
+                return visit(chosen);
+            }
+            return ControlFlow::Continue(());
+        };
+
+        if prune && total > self.target {
+            return ControlFlow::Continue(());
+        }
+
+        for operator in operators {
+            // An overflowing total is always too big, since the target fits in a `u64`.
+            let Some(total) = operator.apply(total, *next) else {
+                continue;
+            };
+
+            chosen.push(*operator);
+            let flow = self.reaches(total, rest, operators, prune, chosen, visit);
+            chosen.pop();
+            flow?;
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    /// Works back from the target, peeling operands off the end by undoing each operator.
+    ///
+    /// Most operators can't be undone for most totals (the total isn't divisible by the
+    /// operand, or doesn't end in its digits), so whole branches are dropped right away.
+    fn visit_backwards<'o>(&self, operators: &[Operator<'o>], visit: &mut Visitor<'_, 'o>) -> ControlFlow<()> {
+        let mut chosen = Vec::with_capacity(self.operands.len());
+        self.unreaches(self.target, &self.operands, operators, &mut chosen, visit)
+    }
+
+    fn unreaches<'o>(
+        &self,
+        total: u64,
+        operands: &[u64],
+        operators: &[Operator<'o>],
+        chosen: &mut Vec<Operator<'o>>,
+        visit: &mut Visitor<'_, 'o>,
+    ) -> ControlFlow<()> {
+        let Some((last, rest)) = operands.split_last() else {
+            return ControlFlow::Continue(());
+        };
+
+        if rest.is_empty() {
+            if total == *last {
+                // The operators were chosen from the last gap to the first.
+                let in_order: Vec<_> = chosen.iter().rev().copied().collect();
+                return visit(&in_order);
+            }
+            return ControlFlow::Continue(());
+        }
+
+        for operator in operators {
+            let Some(total) = operator.unapply(total, *last) else {
+                continue;
+            };
+
+            chosen.push(*operator);
+            let flow = self.unreaches(total, rest, operators, chosen, visit);
+            chosen.pop();
+            flow?;
+        }
+
+        ControlFlow::Continue(())
+    }
+}
+
+/// Called with the operators of a solved [Equation]. Returns [ControlFlow::Break] to stop searching.
+type Visitor<'v, 'o> = dyn FnMut(&[Operator<'o>]) -> ControlFlow<()> + 'v;
+
+/// An [Equation] along with the operators that solve it, drawn as `target = n1 op n2 op ...`.
+struct Solution<'e, 'o> {
+    equation: &'e Equation,
+    operators: Vec<Operator<'o>>,
 }
 
+impl Display for Solution<'_, '_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Equation { target, operands } = self.equation;
+        let Some((first, rest)) = operands.split_first() else {
+            return write!(f, "{target} =");
+        };
+
+        write!(f, "{target} = {first}")?;
+        for (operator, operand) in self.operators.iter().zip(rest) {
+            write!(f, " {} {operand}", operator.symbol())?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_equation(input: &str) -> Option<Equation> {
+    let (target, operands) = input.split_once(':')?;
+    let operands = operands
+        .split_whitespace()
+        .map(|n| n.parse().ok())
+        .collect::<Option<_>>()?;
+
+    Some(Equation {
+        target: target.trim().parse().ok()?,
+        operands,
+    })
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lcg::Lcg;
+
+    const EXAMPLE: &str = "\
+190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20";
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse("<EXAMPLE>")), "<RESULT>");
+        assert_eq!(part1(&parse(EXAMPLE)), 3749);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse("<EXAMPLE>")), "<RESULT>");
+        assert_eq!(part2(&parse(EXAMPLE)), 11387);
+    }
+
+    #[test]
+    fn custom_operators() {
+        let subtract = |lhs: u64, rhs: u64| lhs.checked_sub(rhs);
+        let modulus = 7;
+        let add_mod = move |lhs: u64, rhs: u64| Some((lhs + rhs) % modulus);
+
+        let equation = parse_equation("10: 20 5 5").unwrap();
+        assert!(!equation.is_solvable(&PART2_OPERATORS));
+        assert!(equation.is_solvable(&[Operator::Custom("-", &subtract)]));
+
+        // 6 + 5 wraps around to 4, which only works without pruning.
+        let equation = parse_equation("4: 6 5").unwrap();
+        assert!(equation.is_solvable(&[Operator::Add, Operator::Custom("+%", &add_mod)]));
+    }
+
+    #[test]
+    fn concatenate_overflow() {
+        let equation = parse_equation("18446744073709551615: 18446744073709551 615").unwrap();
+        assert!(equation.is_solvable(&PART2_OPERATORS));
+
+        let equation = parse_equation("1: 18446744073709551615 1").unwrap();
+        assert!(!equation.is_solvable(&PART2_OPERATORS));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn backwards_matches_forwards() {
+        let mut lcg = Lcg::new(0x2024_0007);
+        let mut random = |bound: u64| lcg.below(bound);
+
+        for _ in 0..2000 {
+            let operands: Vec<u64> = (0..=random(8)).map(|_| random(30) + 1).collect();
+
+            // Build a target that's solvable, then nudge it half of the time.
+            let mut target = operands[0];
+            for operand in &operands[1..] {
+                let operator = PART2_OPERATORS[random(3) as usize];
+                target = operator.apply(target, *operand).unwrap_or(u64::MAX);
+            }
+            if random(2) == 0 {
+                target = target.saturating_add(random(5));
+            }
+
+            let equation = Equation { target, operands };
+            for operators in [&PART1_OPERATORS[..], &PART2_OPERATORS[..]] {
+                let mut backwards = Vec::new();
+                let _ = equation.visit_backwards(operators, &mut |chosen| {
+                    backwards.push(chosen.iter().map(Operator::symbol).collect::<String>());
+                    ControlFlow::Continue(())
+                });
+
+                let mut forwards = Vec::new();
+                let _ = equation.visit_forwards(operators, &mut |chosen| {
+                    forwards.push(chosen.iter().map(Operator::symbol).collect::<String>());
+                    ControlFlow::Continue(())
+                });
+
+                backwards.sort_unstable();
+                forwards.sort_unstable();
+                assert_eq!(backwards, forwards, "{equation:?} with {operators:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn zero_operands() {
+        // Multiplying by zero can't be undone, so this has to search forwards.
+        let equation = parse_equation("0: 5 3 0").unwrap();
+        assert!(equation.is_solvable(&PART1_OPERATORS));
+
+        let equation = parse_equation("7: 5 0 2").unwrap();
+        assert!(equation.is_solvable(&PART1_OPERATORS));
+    }
+
+    #[test]
+    fn solutions_example() {
+        let equations = parse(EXAMPLE);
+        let render = |equation: &Equation, operators: &[Operator]| {
+            let mut rendered: Vec<_> = equation.solutions(operators).iter().map(ToString::to_string).collect();
+            rendered.sort_unstable();
+            rendered
+        };
+
+        assert_eq!(render(&equations[0], &PART1_OPERATORS), ["190 = 10 * 19"]);
+        assert_eq!(render(&equations[1], &PART1_OPERATORS), ["3267 = 81 * 40 + 27", "3267 = 81 + 40 * 27"]);
+        assert_eq!(render(&equations[8], &PART1_OPERATORS), ["292 = 11 + 6 * 16 + 20"]);
+        assert_eq!(render(&equations[4], &PART2_OPERATORS), ["7290 = 6 * 8 || 6 * 15"]);
+        assert!(render(&equations[2], &PART2_OPERATORS).is_empty());
+
+        assert_eq!(equations[1].count_solutions(&PART1_OPERATORS), 2);
+        assert_eq!(equations[2].count_solutions(&PART2_OPERATORS), 0);
+        assert_eq!(
+            equations[6].solution(&PART2_OPERATORS).map(|solution| solution.to_string()),
+            Some("192 = 17 || 8 + 14".to_string())
+        );
+    }
+
+    #[test]
+    fn solutions_evaluate_to_target() {
+        let equation = parse_equation("5238563720270: 5 238 5 5 4 9 720 27 2 5").unwrap();
+        let solutions = equation.solutions(&PART2_OPERATORS);
+        assert!(!solutions.is_empty());
+        assert_eq!(solutions.len(), equation.count_solutions(&PART2_OPERATORS));
+
+        for solution in solutions {
+            let total = solution
+                .operators
+                .iter()
+                .zip(&equation.operands[1..])
+                .try_fold(equation.operands[0], |total, (operator, operand)| operator.apply(total, *operand));
+            assert_eq!(total, Some(equation.target), "{solution}");
+        }
+    }
+}
@@ -1,31 +1,337 @@
+use std::collections::{HashMap, HashSet};
+
 use aoc_runner_derive::{aoc, aoc_generator};
+
 #[aoc_generator(day8)]
-fn parse(_input: &str) -> String {
-    todo!()
+fn parse(input: &str) -> AntennaMap {
+    let mut antennas: HashMap<char, Vec<Coord>> = HashMap::new();
+    let (mut width, mut height) = (0, 0);
+
+    for (y, line) in input.lines().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            // The puzzle draws antinodes as `#` in its examples. They aren't antennas.
+            if c == '.' || c == '#' {
+                continue;
+            }
+
+            antennas.entry(c).or_default().push((x as isize, y as isize));
+        }
+
+        width = width.max(line.len() as isize);
+        height = y as isize + 1;
+    }
+
+    AntennaMap {
+        width,
+        height,
+        antennas,
+    }
 }
 
 #[aoc(day8, part1)]
-fn part1(_input: &str) -> String {
-    todo!()
+fn part1(map: &AntennaMap) -> usize {
+    map.antinodes(Rule::Twice).len()
 }
 
 #[aoc(day8, part2)]
-fn part2(_input: &str) -> String {
-    todo!()
+fn part2(map: &AntennaMap) -> usize {
+    map.antinodes(Rule::Resonant).len()
+}
+
+/// Represents a `(x, y)` coordinate pair. Signed, since antinodes can land outside the map.
+type Coord = (isize, isize);
+
+/// Decides where a pair of antennas with the same frequency creates antinodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rule {
+    /// Part one: on either side of the pair, where one antenna is twice as far away as the other.
+    Twice,
+    /// Part two: on every grid position in line with the pair, including the antennas themselves.
+    Resonant,
 }
 
+/// The antennas on the roof, grouped by frequency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AntennaMap {
+    width: isize,
+    height: isize,
+    antennas: HashMap<char, Vec<Coord>>,
+}
+
+impl AntennaMap {
+    /// Returns `true` if `(x, y)` is inside the map.
+    fn contains(&self, (x, y): Coord) -> bool {
+        (0..self.width).contains(&x) && (0..self.height).contains(&y)
+    }
+
+    /// Returns every position inside the map with an antinode of any frequency.
+    fn antinodes(&self, rule: Rule) -> HashSet<Coord> {
+        self.antennas
+            .values()
+            .flat_map(|antennas| self.frequency_antinodes(antennas, rule))
+            .collect()
+    }
+
+    /// Returns every position inside the map with an antinode created by `antennas`,
+    /// which should all share the same frequency.
+    fn frequency_antinodes(&self, antennas: &[Coord], rule: Rule) -> HashSet<Coord> {
+        let mut antinodes = HashSet::new();
+
+        for (idx, a) in antennas.iter().enumerate() {
+            // Visit every pair once. Both rules are symmetric, so the order doesn't matter.
+            for b in &antennas[idx + 1..] {
+                let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+
+                match rule {
+                    Rule::Twice => {
+                        antinodes.extend(
+                            [(a.0 - dx, a.1 - dy), (b.0 + dx, b.1 + dy)]
+                                .into_iter()
+                                .filter(|coord| self.contains(*coord)),
+                        );
+                    }
+                    Rule::Resonant => {
+                        // Reducing the step by the gcd makes sure no grid position on the
+                        // line gets skipped, even between the two antennas.
+                        let divisor = gcd(dx.unsigned_abs(), dy.unsigned_abs()) as isize;
+                        let step = (dx / divisor, dy / divisor);
+
+                        for step in [step, (-step.0, -step.1)] {
+                            let mut coord = *a;
+                            while self.contains(coord) {
+                                antinodes.insert(coord);
+                                coord = (coord.0 + step.0, coord.1 + step.1);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        antinodes
+    }
+
+    /// Returns how each frequency contributes to the antinodes, sorted by frequency.
+    #[allow(dead_code)]
+    fn breakdown(&self, rule: Rule) -> Vec<FrequencyReport> {
+        let antinodes: HashMap<char, HashSet<Coord>> = self
+            .antennas
+            .iter()
+            .map(|(frequency, antennas)| (*frequency, self.frequency_antinodes(antennas, rule)))
+            .collect();
+        let antennas: HashSet<Coord> = self.antennas.values().flatten().copied().collect();
+
+        let mut reports: Vec<_> = antinodes
+            .iter()
+            .map(|(frequency, own)| {
+                let on_antennas = own.intersection(&antennas).copied().collect();
+                let shared = own
+                    .iter()
+                    .filter(|coord| {
+                        antinodes
+                            .iter()
+                            .any(|(other, theirs)| other != frequency && theirs.contains(coord))
+                    })
+                    .copied()
+                    .collect();
+
+                FrequencyReport {
+                    frequency: *frequency,
+                    antinodes: own.clone(),
+                    on_antennas,
+                    shared,
+                }
+            })
+            .collect();
+
+        reports.sort_unstable_by_key(|report| report.frequency);
+        reports
+    }
+
+    /// Draws the map the way the puzzle does: every antinode is a `#`, unless there's an
+    /// antenna on top of it.
+    #[allow(dead_code)]
+    fn render(&self, rule: Rule) -> String {
+        self.render_covering(rule, &[])
+    }
+
+    /// Draws the map like [AntennaMap::render], except antennas with one of the `covered`
+    /// frequencies are drawn underneath antinodes instead of on top of them.
+    #[allow(dead_code)]
+    fn render_covering(&self, rule: Rule, covered: &[char]) -> String {
+        let antennas: HashMap<Coord, char> = self
+            .antennas
+            .iter()
+            .filter(|(frequency, _)| !covered.contains(frequency))
+            .flat_map(|(frequency, antennas)| antennas.iter().map(|coord| (*coord, *frequency)))
+            .collect();
+        let antinodes = self.antinodes(rule);
+
+        // The `+1` here is for the newlines at the end of each row.
+        let mut buf = String::with_capacity(((self.width + 1) * self.height) as usize);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                buf.push(match antennas.get(&(x, y)) {
+                    Some(frequency) => *frequency,
+                    None if antinodes.contains(&(x, y)) => '#',
+                    None => '.',
+                });
+            }
+            buf.push('\n');
+        }
+
+        buf
+    }
+}
+
+/// How a single frequency contributes to the antinodes on an [AntennaMap].
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FrequencyReport {
+    frequency: char,
+    /// Every antinode created by this frequency's antennas.
+    antinodes: HashSet<Coord>,
+    /// The antinodes that land on top of an antenna, of any frequency.
+    on_antennas: HashSet<Coord>,
+    /// The antinodes that at least one other frequency creates as well.
+    shared: HashSet<Coord>,
+}
+
+/// Returns the greatest common divisor of `a` and `b`.
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "\
+............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............";
+
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse("<EXAMPLE>")), "<RESULT>");
+        assert_eq!(part1(&parse(EXAMPLE)), 14);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse("<EXAMPLE>")), "<RESULT>");
+        assert_eq!(part2(&parse(EXAMPLE)), 34);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn resonant_harmonics_t_example() {
+        let map = parse(
+            "\
+T....#....
+...T......
+.T....#...
+.........#
+..#.......
+..........
+...#......
+..........
+....#.....
+..........",
+        );
+        assert_eq!(map.antinodes(Rule::Resonant).len(), 9);
+    }
+
+    #[test]
+    fn resonant_steps_are_reduced() {
+        // The antennas are four apart, but the positions two apart are on the line too.
+        let map = parse("a...a");
+        assert_eq!(map.antinodes(Rule::Twice).len(), 0);
+        assert_eq!(map.antinodes(Rule::Resonant).len(), 5);
+
+        let map = parse("a.....\n......\n..a...");
+        assert_eq!(
+            map.antinodes(Rule::Resonant),
+            HashSet::from([(0, 0), (1, 1), (2, 2)])
+        );
+    }
+
+    #[test]
+    fn render_example() {
+        let map = parse(EXAMPLE);
+        assert_eq!(
+            map.render(Rule::Twice),
+            "\
+......#....#
+...#....0...
+....#0....#.
+..#....0....
+....0....#..
+.#....A.....
+...#........
+#......#....
+........A...
+.........A..
+..........#.
+..........#.
+"
+        );
+        let resonant = "\
+##....#....#
+.#.#....0...
+..#.#0....#.
+..##...0....
+....0....#..
+.#...#A....#
+...#..#.....
+#....#.#....
+..#.....A...
+....#....A..
+.#........#.
+...#......##
+";
+        assert_eq!(map.render(Rule::Resonant), resonant);
+        // The puzzle draws all three `A` antennas as `#` here, but keeps the `0` antennas.
+        assert_eq!(
+            map.render_covering(Rule::Resonant, &['A']),
+            "\
+##....#....#
+.#.#....0...
+..#.#0....#.
+..##...0....
+....0....#..
+.#...##....#
+...#..#.....
+#....#.#....
+..#.....#...
+....#....#..
+.#........#.
+...#......##
+"
+        );
+    }
+
+    #[test]
+    fn breakdown_example() {
+        let map = parse(EXAMPLE);
+        let reports = map.breakdown(Rule::Twice);
+        let frequencies: Vec<_> = reports.iter().map(|report| report.frequency).collect();
+        assert_eq!(frequencies, ['0', 'A']);
+
+        let (zero, a) = (&reports[0], &reports[1]);
+        // One of the `0` antinodes lands on the topmost `A` antenna.
+        assert_eq!(zero.on_antennas, HashSet::from([(6, 5)]));
+        assert!(a.on_antennas.is_empty());
+        // Antinodes from both frequencies overlap in one place, so the total is one short.
+        assert_eq!(zero.shared, a.shared);
+        assert_eq!(zero.antinodes.len() + a.antinodes.len() - zero.shared.len(), 14);
+    }
+}
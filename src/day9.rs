@@ -1,31 +1,447 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
 use aoc_runner_derive::{aoc, aoc_generator};
+
 #[aoc_generator(day9)]
-fn parse(_input: &str) -> String {
-    todo!()
+fn parse(input: &str) -> Disk {
+    let mut files = Vec::with_capacity(input.len().div_ceil(2));
+    let mut free = Vec::with_capacity(input.len() / 2);
+    let mut start = 0usize;
+
+    // The digits alternate between the length of a file and the length of the free space after it.
+    for (idx, c) in input.trim().chars().enumerate() {
+        let len = c.to_digit(10).expect("The disk map should only contain digits.") as usize;
+        let span = Span { start, len };
+
+        if idx % 2 == 0 {
+            files.push(span);
+        } else if len > 0 {
+            free.push(span);
+        }
+
+        start += len;
+    }
+
+    Disk { files, free }
 }
 
 #[aoc(day9, part1)]
-fn part1(_input: &str) -> String {
-    todo!()
+fn part1(disk: &Disk) -> usize {
+    checksum(&disk.compact_blocks())
 }
 
 #[aoc(day9, part2)]
-fn part2(_input: &str) -> String {
-    todo!()
+fn part2(disk: &Disk) -> usize {
+    disk.compact_files().checksum()
+}
+
+/// Same as [part2], but scans for free space from the start of the disk for every file.
+#[aoc(day9, part2, naive)]
+fn part2_naive(disk: &Disk) -> usize {
+    disk.compact_files_naive().checksum()
+}
+
+/// A run of consecutive blocks on a [Disk].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Span {
+    start: usize,
+    len: usize,
+}
+
+/// The files and free space on the amphipod's disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Disk {
+    /// Where each file is stored. A file's ID is its index.
+    files: Vec<Span>,
+    /// The runs of free space, in the order they appear on the disk.
+    free: Vec<Span>,
+}
+
+impl Disk {
+    /// Returns the total number of blocks on the disk, used or not.
+    fn len(&self) -> usize {
+        let end = |span: &Span| span.start + span.len;
+        let files = self.files.iter().map(end).max().unwrap_or(0);
+        let free = self.free.iter().map(end).max().unwrap_or(0);
+        files.max(free)
+    }
+
+    /// Returns the ID of the file stored in each block, or `None` for free blocks.
+    fn blocks(&self) -> Vec<Option<usize>> {
+        let mut blocks = vec![None; self.len()];
+        for (id, file) in self.files.iter().enumerate() {
+            blocks[file.start..file.start + file.len].fill(Some(id));
+        }
+        blocks
+    }
+
+    /// Moves blocks one at a time from the end of the disk into the leftmost free block,
+    /// until there are no gaps left between files. Files can end up split into pieces.
+    fn compact_blocks(&self) -> Vec<Option<usize>> {
+        let mut blocks = self.blocks();
+        let (mut left, mut right) = (0usize, blocks.len());
+
+        loop {
+            // Find the leftmost free block...
+            while left < right && blocks[left].is_some() {
+                left += 1;
+            }
+            // ...and the rightmost used block.
+            while left < right && blocks[right - 1].is_none() {
+                right -= 1;
+            }
+            if left + 1 >= right {
+                break;
+            }
+
+            blocks.swap(left, right - 1);
+        }
+
+        blocks
+    }
+
+    /// Moves each file exactly once, in order of descending file ID, into the leftmost span of
+    /// free space that can fit the whole file. Files that don't fit anywhere to their left stay put.
+    ///
+    /// Files are at most nine blocks long, so the spans of free space are sorted into one
+    /// min-heap of start offsets per length, with every span of nine blocks or more sharing the
+    /// last one (compacted disks can have much longer spans). The leftmost span that fits a
+    /// file is then at the top of one of (at most) nine heaps, which makes each move logarithmic.
+    fn compact_files(&self) -> Disk {
+        const LONGEST: usize = 9;
+
+        let mut files = self.files.clone();
+        // The `(start, len)` of each span of free space, by length.
+        let mut gaps: [BinaryHeap<Reverse<(usize, usize)>>; LONGEST + 1] = Default::default();
+
+        for gap in &self.free {
+            gaps[gap.len.min(LONGEST)].push(Reverse((gap.start, gap.len)));
+        }
+
+        for file in files.iter_mut().rev() {
+            // The leftmost gap among every length the file fits in.
+            let leftmost = (file.len.clamp(1, LONGEST)..gaps.len())
+                .filter_map(|bucket| gaps[bucket].peek().map(|Reverse(gap)| (*gap, bucket)))
+                .min();
+
+            // Files only ever move to the left.
+            let Some(((start, len), bucket)) = leftmost
+                .filter(|((start, len), _)| *start < file.start && *len >= file.len)
+            else {
+                continue;
+            };
+
+            gaps[bucket].pop();
+            file.start = start;
+
+            // Whatever the file didn't use is still free, just shorter.
+            let left = len - file.len;
+            if left > 0 {
+                gaps[left.min(LONGEST)].push(Reverse((start + file.len, left)));
+            }
+        }
+
+        self.with_files(files)
+    }
+
+    /// Same as [Disk::compact_files], but scans every span of free space from the start of the
+    /// disk for each file, which is quadratic in the size of the disk.
+    fn compact_files_naive(&self) -> Disk {
+        let mut files = self.files.clone();
+        let mut free = self.free.clone();
+
+        for file in files.iter_mut().rev() {
+            let Some(gap) = free
+                .iter_mut()
+                // Files only ever move to the left.
+                .take_while(|gap| gap.start < file.start)
+                .find(|gap| gap.len >= file.len)
+            else {
+                continue;
+            };
+
+            file.start = gap.start;
+            gap.start += file.len;
+            gap.len -= file.len;
+
+            // The space the file left behind is never reused: every file that's left to move
+            // sits to the left of it.
+        }
+
+        self.with_files(files)
+    }
+
+    /// Returns a disk of the same size with `files` stored on it, and free space everywhere else.
+    fn with_files(&self, files: Vec<Span>) -> Disk {
+        let mut stored: Vec<Span> = files.iter().copied().filter(|file| file.len > 0).collect();
+        stored.sort_unstable_by_key(|file| file.start);
+
+        let mut free = Vec::with_capacity(stored.len() + 1);
+        let mut start = 0usize;
+        for file in stored.iter().chain([&Span { start: self.len(), len: 0 }]) {
+            if file.start > start {
+                free.push(Span {
+                    start,
+                    len: file.start - start,
+                });
+            }
+            start = file.start + file.len;
+        }
+
+        Disk { files, free }
+    }
+
+    /// Returns the filesystem checksum: the sum of each block's position multiplied by the
+    /// ID of the file stored in it.
+    fn checksum(&self) -> usize {
+        self.files
+            .iter()
+            .enumerate()
+            .map(|(id, file)| id * (file.start..file.start + file.len).sum::<usize>())
+            .sum()
+    }
+}
+
+/// Returns the filesystem checksum of a disk laid out as `blocks` (see [Disk::checksum]).
+fn checksum(blocks: &[Option<usize>]) -> usize {
+    blocks
+        .iter()
+        .enumerate()
+        .filter_map(|(pos, id)| id.map(|id| pos * id))
+        .sum()
+}
+
+/// Draws a disk laid out as `blocks` in the puzzle's notation: one character per block, with
+/// `.` for free space and the file's ID for used blocks.
+///
+/// IDs above 9 don't fit in a single character, so those files get a letter instead (in the
+/// order they first show up), and a legend line for each letter is added after the layout.
+/// Once all 52 letters are taken, the rest of the files are drawn as their ID in brackets
+/// (`[62]`) for every block, so no two files ever look the same.
+#[allow(dead_code)]
+fn render(blocks: &[Option<usize>]) -> String {
+    const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+    let mut layout = String::with_capacity(blocks.len() + 1);
+    // The letter drawn for each file ID above 9, and the IDs in the order they got one.
+    let mut letters: HashMap<usize, char> = HashMap::new();
+    let mut legend: Vec<usize> = Vec::new();
+
+    for block in blocks {
+        match *block {
+            None => layout.push('.'),
+            Some(id) if id <= 9 => layout.push(char::from(b'0' + id as u8)),
+            Some(id) => {
+                if !letters.contains_key(&id) && legend.len() < LETTERS.len() {
+                    letters.insert(id, char::from(LETTERS[legend.len()]));
+                    legend.push(id);
+                }
+
+                match letters.get(&id) {
+                    Some(letter) => layout.push(*letter),
+                    None => layout.push_str(&format!("[{id}]")),
+                }
+            }
+        }
+    }
+    layout.push('\n');
+
+    for (letter, id) in LETTERS.iter().zip(&legend) {
+        layout.push_str(&format!("{} = {id}\n", char::from(*letter)));
+    }
+
+    layout
 }
 
+/// Writes a disk laid out as `blocks` back out as a dense disk map: the lengths of each file
+/// and each span of free space, alternating.
+///
+/// Dense disk maps number files by the order they appear in, so file IDs don't survive the
+/// trip once a disk has been compacted (and neither does the order of pieces of the same file).
+/// Since a digit only goes up to 9, longer runs are split up by empty files or free space.
+#[allow(dead_code)]
+fn to_dense(blocks: &[Option<usize>]) -> String {
+    let mut dense = String::with_capacity(blocks.len());
+    // Dense disk maps always start with a file.
+    let mut expect_file = true;
+
+    for run in blocks.chunk_by(|a, b| a == b) {
+        let is_file = run[0].is_some();
+
+        for (idx, chunk) in run.chunks(9).enumerate() {
+            // Two files (or two spans of free space) in a row need an empty one of the
+            // other kind between them.
+            if idx > 0 || is_file != expect_file {
+                dense.push('0');
+            }
+
+            // SAFETY: `chunk` is never longer than nine blocks.
+            dense.push(char::from_digit(chunk.len() as u32, 10).unwrap());
+            expect_file = !is_file;
+        }
+    }
+
+    dense
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lcg::Lcg;
+
+    const EXAMPLE: &str = "2333133121414131402";
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse("<EXAMPLE>")), "<RESULT>");
+        assert_eq!(part1(&parse(EXAMPLE)), 1928);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse("<EXAMPLE>")), "<RESULT>");
+        assert_eq!(part2(&parse(EXAMPLE)), 2858);
+    }
+
+    #[test]
+    fn compact_small_example() {
+        // 0..111....22222
+        let disk = parse("12345");
+        let blocks = disk.compact_blocks();
+        assert_eq!(
+            blocks,
+            [0, 2, 2, 1, 1, 1, 2, 2, 2]
+                .map(Some)
+                .into_iter()
+                .chain([None; 6])
+                .collect::<Vec<_>>()
+        );
+
+        // None of the files fit in the gaps to their left.
+        assert_eq!(disk.compact_files().blocks(), disk.blocks());
+        assert_eq!(disk.compact_files().checksum(), checksum(&disk.blocks()));
+    }
+
+    #[test]
+    fn compact_files_matches_naive() {
+        for disk in [parse(EXAMPLE), parse("12345"), parse(&synthetic_disk_map(10_000))] {
+            let compacted = disk.compact_files();
+            assert_eq!(compacted, disk.compact_files_naive());
+            assert_eq!(compacted.len(), disk.len());
+        }
+    }
+
+    #[test]
+    fn compacted_free_space() {
+        // 00992111777.44.333....5555.6666.....8888..
+        let disk = parse(EXAMPLE).compact_files();
+        let free: Vec<_> = disk.free.iter().map(|gap| (gap.start, gap.len)).collect();
+        assert_eq!(free, [(11, 1), (14, 1), (18, 4), (26, 1), (31, 5), (40, 2)]);
+    }
+
+    #[test]
+    fn compact_files_twice() {
+        for map in [EXAMPLE.to_string(), synthetic_disk_map(2_000)] {
+            let once = parse(&map).compact_files();
+            let twice = once.compact_files();
+            assert_eq!(twice.blocks(), once.compact_files_naive().blocks());
+            assert_eq!(twice.checksum(), once.compact_files_naive().checksum());
+        }
+
+        // Compacting merges free space into spans much longer than nine blocks.
+        let once = parse(&synthetic_disk_map(2_000)).compact_files();
+        assert!(once.free.iter().any(|gap| gap.len > 9));
+    }
+
+    #[test]
+    fn render_example() {
+        let disk = parse(EXAMPLE);
+        assert_eq!(render(&disk.blocks()), "00...111...2...333.44.5555.6666.777.888899\n");
+        assert_eq!(render(&disk.compact_blocks()), "0099811188827773336446555566..............\n");
+        assert_eq!(render(&disk.compact_files().blocks()), "00992111777.44.333....5555.6666.....8888..\n");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn render_legend() {
+        let disk = parse("11111111111111111111111");
+        assert_eq!(render(&disk.blocks()), "0.1.2.3.4.5.6.7.8.9.a.b\na = 10\nb = 11\n");
+
+        // 10 + 52 files use up every digit and letter, so files 62 and up get brackets.
+        let disk = parse(&"20".repeat(64));
+        let rendered = render(&disk.blocks());
+        let letters = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let doubled: String = letters.chars().flat_map(|letter| [letter, letter]).collect();
+        assert!(rendered.starts_with(&format!("00112233445566778899{doubled}[62][62][63][63]\n")));
+        assert!(rendered.contains("\na = 10\nb = 11\n"));
+        assert!(rendered.ends_with("\nZ = 61\n"));
+        assert!(!rendered.contains("62\n"));
+    }
+
+    #[test]
+    fn dense_round_trip() {
+        let disk = parse(EXAMPLE);
+        assert_eq!(to_dense(&disk.blocks()), EXAMPLE);
+
+        let compacted = disk.compact_blocks();
+        let dense = to_dense(&compacted);
+        assert_eq!(dense, "2020103030103030102010402905");
+
+        // File IDs are renumbered, but every block stays used or free.
+        let reparsed = parse(&dense);
+        let used = |blocks: &[Option<usize>]| blocks.iter().map(Option::is_some).collect::<Vec<_>>();
+        assert_eq!(used(&reparsed.blocks()), used(&compacted));
+
+        let compacted = disk.compact_files().blocks();
+        assert_eq!(used(&parse(&to_dense(&compacted)).blocks()), used(&compacted));
+    }
+
+    /// Returns a disk map `len` digits long, with the same kind of digits as the puzzle input.
+    fn synthetic_disk_map(len: usize) -> String {
+        let mut lcg = Lcg::new(0x2024_0009);
+        (0..len)
+            .map(|idx| {
+                let digit = lcg.below(10);
+                // Files are never empty.
+                let digit = if idx % 2 == 0 { digit.max(1) } else { digit };
+                char::from_digit(digit as u32, 10).unwrap()
+            })
+            .collect()
+    }
+
+    mod bench {
+        extern crate test;
+
+        use super::*;
+        use test::Bencher;
+
+        const INPUT: &str = include_str!("../input/2024/day9.txt");
+
+        #[bench]
+        fn compact_files_input(b: &mut Bencher) {
+            let disk = parse(INPUT);
+            b.iter(|| disk.compact_files());
+        }
+
+        #[bench]
+        fn compact_files_naive_input(b: &mut Bencher) {
+            let disk = parse(INPUT);
+            b.iter(|| disk.compact_files_naive());
+        }
+
+        #[bench]
+        fn compact_files_synthetic(b: &mut Bencher) {
+            let disk = parse(&synthetic_disk_map(1_000_000));
+            b.iter(|| disk.compact_files());
+        }
+
+        // The naive version takes a while on a disk this big, so it only runs when asked for
+        // (`cargo bench -- --ignored`).
+        #[bench]
+        #[ignore]
+        fn compact_files_naive_synthetic(b: &mut Bencher) {
+            let disk = parse(&synthetic_disk_map(1_000_000));
+            b.iter(|| disk.compact_files_naive());
+        }
+    }
+}
@@ -1,4 +1,5 @@
 #![feature(array_windows)]
+#![cfg_attr(test, feature(test))]
 
 mod day25;
 mod day24;
@@ -26,6 +27,11 @@ mod day3;
 mod day2;
 mod day1;
 
+#[cfg(test)]
+mod lcg;
+#[cfg(test)]
+mod temp_dir;
+
 use aoc_runner_derive::*;
 
 aoc_lib!{ year = 2024 }
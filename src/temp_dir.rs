@@ -0,0 +1,23 @@
+use std::path::PathBuf;
+
+/// A directory for tests to write files into, removed again once it's dropped (even if the
+/// test panics). Each test process gets its own, so test runs can't trip over each other.
+pub(crate) struct TempDir(PathBuf);
+
+impl TempDir {
+    pub(crate) fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("aoc-2024-{name}-{}", std::process::id()));
+        Self(dir)
+    }
+
+    pub(crate) fn path(&self) -> &str {
+        self.0.to_str().expect("The temporary directory's path wasn't UTF-8.")
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        // It's fine if nothing was ever written to it.
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}